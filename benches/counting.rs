@@ -0,0 +1,125 @@
+// Throughput of the counting hot path on synthetic meet results:
+//
+//     cargo bench --bench counting
+//     STANDARDS_BENCH_RESULTS=250000 cargo bench --bench counting
+//
+// Criterion reports each size in results per second (elements/s), so runs can
+// be compared before and after a change to the aggregation.
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::path::Path;
+use std::rc::Rc;
+
+use standards::{
+    count_qualifiers, count_unique_qualifiers, CutTimes, EventStandards, MeetResult,
+    DEFAULT_CUT_LEVEL,
+};
+
+// Result counts benchmarked unless STANDARDS_BENCH_RESULTS gives a
+// comma-separated list of its own
+const SIZES: &[usize] = &[1_000, 10_000, 100_000];
+
+const GENDERS: &[&str] = &["Men", "Women"];
+const AGE_GROUPS: &[&str] = &["10", "11", "12", "13-14", "15"];
+// Swimmer ages in the meet files, a few outside the age groups at each end
+const AGES: &[&str] = &["9", "10", "11", "12", "13", "14", "15", "16"];
+// Normalized event and a 10&U cut in seconds; older groups get faster cuts
+const EVENTS: &[(&str, f64)] = &[
+    ("50Fr", 35.0), ("100Fr", 78.0), ("200Fr", 170.0), ("50Bk", 41.0), ("100Bk", 88.0),
+    ("50Br", 46.0), ("100Br", 99.0), ("50Fl", 40.0), ("100Fl", 90.0), ("200Me", 190.0),
+];
+// Results per athlete on average, so the unique counts see repeat names
+const RESULTS_PER_ATHLETE: usize = 6;
+
+fn sizes() -> Vec<usize> {
+    match std::env::var("STANDARDS_BENCH_RESULTS") {
+        Ok(sizes) => sizes.split(',').filter_map(|size| size.trim().parse().ok()).collect(),
+        Err(_) => SIZES.to_vec(),
+    }
+}
+
+// Every gender, event and age group with one cut at DEFAULT_CUT_LEVEL
+fn synthetic_standards() -> HashMap<String, EventStandards> {
+    GENDERS.iter()
+        .map(|gender| {
+            let events = EVENTS.iter()
+                .map(|(event, cut)| {
+                    let ages = AGE_GROUPS.iter()
+                        .enumerate()
+                        .map(|(idx, age)| {
+                            let cut = cut * (1.0 - 0.04 * idx as f64);
+                            (age.to_string(), CutTimes::from([(DEFAULT_CUT_LEVEL.to_string(), cut)]))
+                        })
+                        .collect();
+                    (event.to_string(), ages)
+                })
+                .collect();
+            (gender.to_string(), events)
+        })
+        .collect()
+}
+
+// `count` SCY results spread over the genders, ages and events, with times
+// from 10% under to 10% over the 10&U cut (a fixed LCG, so every run counts
+// the same swims)
+fn synthetic_results(count: usize) -> Vec<MeetResult> {
+    let folder: Rc<Path> = Rc::from(Path::new("data"));
+    let meet: Rc<str> = "Bench".into();
+    let course: Rc<str> = "SCY".into();
+    let genders: Vec<Rc<str>> = GENDERS.iter().map(|gender| Rc::from(*gender)).collect();
+    let ages: Vec<Rc<str>> = AGES.iter().map(|age| Rc::from(*age)).collect();
+    let events: Vec<Rc<str>> = EVENTS.iter().map(|(event, _)| Rc::from(*event)).collect();
+    let athletes = (count / RESULTS_PER_ATHLETE).max(1);
+
+    let mut seed: u64 = 0x5eed;
+    let mut next = move || {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (seed >> 33) as usize
+    };
+
+    (0..count)
+        .map(|_| {
+            // An athlete keeps their gender and age across their swims
+            let athlete = next() % athletes;
+            let age = ages[athlete / genders.len() % ages.len()].clone();
+            let event = next() % events.len();
+            let spread = (next() % 2001) as f64 / 10_000.0;
+            MeetResult {
+                folder: folder.clone(),
+                meet: meet.clone(),
+                date: None,
+                course: course.clone(),
+                sex: genders[athlete % genders.len()].clone(),
+                age: age.clone(),
+                age_min: age,
+                event: events[event].clone(),
+                time: EVENTS[event].1 * (0.9 + spread),
+                split_time: None,
+                round: None,
+                name: format!("Athlete {}", athlete),
+                is_relay: false,
+            }
+        })
+        .collect()
+}
+
+fn counting(c: &mut Criterion) {
+    let standards = synthetic_standards();
+
+    let mut group = c.benchmark_group("counting");
+    for size in sizes() {
+        let results = synthetic_results(size);
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::new("count_qualifiers", size), &results, |b, results| {
+            b.iter(|| count_qualifiers(black_box(results), &standards, 0.0))
+        });
+        group.bench_with_input(BenchmarkId::new("count_unique_qualifiers", size), &results, |b, results| {
+            b.iter(|| count_unique_qualifiers(black_box(results), &standards, 0.0))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, counting);
+criterion_main!(benches);
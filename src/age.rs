@@ -0,0 +1,271 @@
+use log::info;
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::LazyLock;
+
+use crate::config::OverAgePolicy;
+use crate::error::AnalysisError;
+use crate::meet::MeetResult;
+use crate::standards::EventStandards;
+
+// "and under"/"and over" suffixes: "10&U", "15&O", "15 & Over", "12 & under"
+static AGE_SUFFIX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\s*&\s*(?:u|under|o|over)$").unwrap()
+});
+
+// Two-year (or wider) age group headers: "11-12", "13 - 14"
+static AGE_RANGE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(\d+)\s*-\s*(\d+)$").unwrap());
+
+pub fn normalize_age(age: &str) -> String {
+    // Remove "&U"/"&O" suffix if present
+    let age = AGE_SUFFIX.replace(age.trim(), "");
+    
+    // Ranges keep both ends without spaces ("13 - 14" -> "13-14")
+    match AGE_RANGE.captures(&age) {
+        Some(caps) => format!("{}-{}", &caps[1], &caps[2]),
+        None => age.into_owned(),
+    }
+}
+
+// Open/senior results have no age; they're matched to the oldest age group
+const OPEN_AGES: &[&str] = &["Open", "Senior", "Sr"];
+
+pub fn is_open_age(age: &str) -> bool {
+    OPEN_AGES.iter().any(|open| open.eq_ignore_ascii_case(age.trim()))
+}
+
+// Whether an athlete age can't be read even after normalizing: not a number
+// and not an open age. find_best_age_range_match matches it to nothing.
+fn is_unparseable_age(age: &str) -> bool {
+    let age = normalize_age(age);
+    !is_open_age(&age) && parse_age(&age).is_none()
+}
+
+// Results per athlete age that can't be read (see is_unparseable_age), in age
+// order. These match no age group, so they count nowhere.
+pub fn unparseable_ages(meet_results: &[MeetResult]) -> BTreeMap<String, usize> {
+    let mut unparseable: BTreeMap<String, usize> = BTreeMap::new();
+    for result in meet_results.iter().filter(|r| is_unparseable_age(&r.age)) {
+        *unparseable.entry(result.age().to_string()).or_insert(0) += 1;
+    }
+    unparseable
+}
+
+// Numeric age from a normalized age string
+pub fn parse_age(age: &str) -> Option<i32> {
+    age.trim().parse::<i32>().ok()
+}
+
+// Inclusive ages covered by a normalized age group: "12" -> (12, 12),
+// "11-12" -> (11, 12)
+pub fn parse_age_range(age: &str) -> Option<(i32, i32)> {
+    if let Some(n) = parse_age(age) {
+        return Some((n, n));
+    }
+    let (low, high) = age.split_once('-')?;
+    let (low, high) = (parse_age(low)?, parse_age(high)?);
+    (low <= high).then_some((low, high))
+}
+
+// Orders age groups by their upper age; anything unparseable goes last
+pub fn age_sort_key(age: &str) -> i32 {
+    parse_age_range(age).map_or(999, |(_, high)| high)
+}
+
+// Age group an exact age is listed under: its own column, or a range column
+// ("11-12") containing it
+pub fn age_group_for<'a>(
+    age: &str,
+    available_ages: impl IntoIterator<Item = &'a String>,
+) -> Option<&'a String> {
+    let age_num = parse_age(age);
+    let mut containing = None;
+    for group in available_ages {
+        if group == age {
+            return Some(group);
+        }
+        let contains = age_num.zip(parse_age_range(group))
+            .is_some_and(|(n, (low, high))| (low..=high).contains(&n));
+        if contains && containing.is_none() {
+            containing = Some(group);
+        }
+    }
+    containing
+}
+
+// Whether an age falls inside an optional min/max range (inclusive). Ages that
+// aren't numbers are outside any active range; age group ranges ("11-12") are
+// inside when they overlap it.
+pub fn age_in_range(age: &str, min_age: Option<i32>, max_age: Option<i32>) -> bool {
+    if min_age.is_none() && max_age.is_none() {
+        return true;
+    }
+    match parse_age_range(age) {
+        Some((low, high)) => {
+            min_age.is_none_or(|min| high >= min) && max_age.is_none_or(|max| low <= max)
+        }
+        None => false,
+    }
+}
+
+pub fn find_best_age_match(athlete_age: &str, available_ages: &[String]) -> Option<String> {
+    find_best_age_range_match(athlete_age, athlete_age, available_ages)
+}
+
+// Age group for a swimmer known to be between age_min and age_max (a 13-14
+// meet file). Age groups may be single ages or ranges ("11-12"). The group
+// containing the upper age is tried first, then the oldest group overlapping
+// the swimmer's range, then the usual clamp/closest rules on the upper age.
+// Ages are normalized first ("12&U" -> "12"); "Open" and "Senior" match the
+// oldest group, and any other unreadable age matches nothing (see
+// unparseable_ages).
+pub fn find_best_age_range_match(
+    age_min: &str,
+    age_max: &str,
+    available_ages: &[String],
+) -> Option<String> {
+    let (age_min, age_max) = (normalize_age(age_min), normalize_age(age_max));
+    
+    // Convert available ages to (low, high) ranges
+    let mut age_nums: Vec<(i32, i32, String)> = available_ages
+        .iter()
+        .filter_map(|a| {
+            parse_age_range(a).map(|(low, high)| (low, high, a.clone()))
+        })
+        .collect();
+    
+    if age_nums.is_empty() {
+        return None;
+    }
+    
+    // Sort by age
+    age_nums.sort_by_key(|(low, high, _)| (*high, *low));
+    
+    if is_open_age(&age_max) {
+        return age_nums.last().map(|(_, _, age_str)| age_str.clone());
+    }
+    let athlete_age_num = parse_age(&age_max)?;
+    let athlete_min_num = parse_age(&age_min).unwrap_or(athlete_age_num);
+    
+    // Find the group containing the age first (an exact single age wins over a range)
+    if let Some((_, _, age_str)) = age_nums.iter()
+        .filter(|(low, high, _)| (*low..=*high).contains(&athlete_age_num))
+        .min_by_key(|(low, high, _)| high - low)
+    {
+        return Some(age_str.clone());
+    }
+    
+    // Then the oldest age group the range covers
+    if let Some((_, _, age_str)) = age_nums.iter().rev()
+        .find(|(low, high, _)| *low <= athlete_age_num && *high >= athlete_min_num)
+    {
+        return Some(age_str.clone());
+    }
+    
+    // If athlete is younger than minimum standard, use the minimum
+    if athlete_age_num < age_nums[0].0 {
+        return Some(age_nums[0].2.clone());
+    }
+    
+    // If athlete is older than maximum standard, use the maximum
+    if let Some((_, high, age_str)) = age_nums.last() {
+        if athlete_age_num > *high {
+            return Some(age_str.clone());
+        }
+    }
+    
+    // Otherwise the nearest group (the age falls in a gap between groups)
+    let closest = age_nums
+        .iter()
+        .min_by_key(|(low, high, _)| (low - athlete_age_num).max(athlete_age_num - high))
+        .map(|(_, _, age_str)| age_str.clone());
+    
+    closest
+}
+
+// Age group a result counts under, used by every count so qualifiers and
+// athlete totals can't drift apart. Matches against the age groups of the
+// result's event, or of the gender's whole standards when the event has none.
+// None when the gender has no standards at all.
+pub fn match_age_group(
+    age_min: &str,
+    age_max: &str,
+    event: &str,
+    gender_standards: &EventStandards,
+) -> Option<String> {
+    find_best_age_range_match(age_min, age_max, &event_age_groups(event, gender_standards))
+}
+
+// Age groups a result in `event` is matched against (see match_age_group)
+fn event_age_groups(event: &str, gender_standards: &EventStandards) -> Vec<String> {
+    match gender_standards.get(event) {
+        Some(event_standards) => event_standards.keys().cloned().collect(),
+        None => gender_standards.values()
+            .flat_map(|event_standards| event_standards.keys().cloned())
+            .collect::<BTreeSet<String>>()
+            .into_iter()
+            .collect(),
+    }
+}
+
+// Whether a swimmer between age_min and age_max is older than every age group
+// match_age_group would consider, so the match falls back to the oldest one.
+// A range file (15-16) is only over age when its lower end is. Open and
+// unreadable ages never are.
+pub fn is_over_age(
+    age_min: &str,
+    age_max: &str,
+    event: &str,
+    gender_standards: &EventStandards,
+) -> bool {
+    let (age_min, age_max) = (normalize_age(age_min), normalize_age(age_max));
+    let youngest = match parse_age(&age_min).or_else(|| parse_age(&age_max)) {
+        Some(n) => n,
+        None => return false,
+    };
+    let oldest = event_age_groups(event, gender_standards).iter()
+        .filter_map(|age| parse_age_range(age))
+        .map(|(_, high)| high)
+        .max();
+    oldest.is_some_and(|oldest| youngest > oldest)
+}
+
+// Applies the over-age policy to results older than every age group for their
+// sex and event: `Clamp` keeps them (they count against the oldest group),
+// `Drop` removes them and `Error` fails on the first one. Returns how many
+// results were over age.
+pub fn apply_over_age_policy(
+    meet_results: &mut Vec<MeetResult>,
+    standards: &HashMap<String, EventStandards>,
+    policy: OverAgePolicy,
+) -> Result<usize, AnalysisError> {
+    let over_age = |result: &MeetResult| {
+        standards.get(result.sex()).is_some_and(|gender_standards| {
+            is_over_age(&result.age_min, &result.age, &result.event, gender_standards)
+        })
+    };
+    
+    let count = meet_results.iter().filter(|r| over_age(r)).count();
+    if count == 0 {
+        return Ok(0);
+    }
+    
+    match policy {
+        OverAgePolicy::Clamp => {
+            info!("{} results are older than every age group and count against the oldest one", count);
+        }
+        OverAgePolicy::Drop => {
+            meet_results.retain(|r| !over_age(r));
+            info!("Dropped {} results older than every age group", count);
+        }
+        OverAgePolicy::Error => {
+            let first = meet_results.iter().find(|r| over_age(r)).expect("counted above");
+            return Err(AnalysisError::OverAge {
+                count,
+                example: format!("{} {} {} {}", first.name, first.sex, first.age, first.event),
+            });
+        }
+    }
+    
+    Ok(count)
+}
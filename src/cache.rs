@@ -0,0 +1,104 @@
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::error::AnalysisError;
+use crate::standards::{load_time_standards_labeled, AgeLabels, EventOrders, EventStandards, StandardsColumns};
+
+// Parsed standards saved next to the workbook. Valid while the workbook's
+// modification time, gender tab mapping, columns, stroke names and required
+// genders are unchanged.
+#[derive(Serialize, Deserialize)]
+struct StandardsCache {
+    modified_secs: u64,
+    modified_nanos: u32,
+    gender_tabs: HashMap<String, String>,
+    columns: StandardsColumns,
+    stroke_names: HashMap<String, String>,
+    required_genders: Vec<String>,
+    standards: HashMap<String, EventStandards>,
+    event_orders: EventOrders,
+    // AgeLabels as a list: JSON object keys can't be (gender, age) pairs
+    age_labels: Vec<((String, String), String)>,
+}
+
+// "timestandards.xlsx" -> "timestandards.cache.json"
+pub fn standards_cache_path(standards_file: &Path) -> PathBuf {
+    standards_file.with_extension("cache.json")
+}
+
+fn modified_time(path: &Path) -> Option<(u64, u32)> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    let since_epoch = modified.duration_since(UNIX_EPOCH).ok()?;
+    Some((since_epoch.as_secs(), since_epoch.subsec_nanos()))
+}
+
+fn read_cache(cache_path: &Path) -> Option<StandardsCache> {
+    let file = File::open(cache_path).ok()?;
+    serde_json::from_reader(BufReader::new(file)).ok()
+}
+
+fn write_cache(cache_path: &Path, cache: &StandardsCache) -> Result<(), AnalysisError> {
+    let mut writer = BufWriter::new(File::create(cache_path)?);
+    serde_json::to_writer(&mut writer, cache)?;
+    writer.flush()?;
+    Ok(())
+}
+
+// load_time_standards_labeled, reusing the cache file when it is fresh. A
+// missing, stale or unreadable cache falls back to parsing the workbook and is
+// rewritten; `use_cache = false` always parses (and still refreshes the cache).
+pub fn load_time_standards_cached(
+    standards_file: &Path,
+    gender_tabs: &HashMap<String, String>,
+    columns: StandardsColumns,
+    stroke_names: &HashMap<String, String>,
+    required_genders: &[String],
+    use_cache: bool,
+) -> Result<(HashMap<String, EventStandards>, EventOrders, AgeLabels), AnalysisError> {
+    let cache_path = standards_cache_path(standards_file);
+    let modified = modified_time(standards_file);
+
+    if use_cache {
+        if let Some(cache) = read_cache(&cache_path) {
+            if Some((cache.modified_secs, cache.modified_nanos)) == modified
+                && cache.gender_tabs == *gender_tabs
+                && cache.columns == columns
+                && cache.stroke_names == *stroke_names
+                && cache.required_genders == required_genders
+            {
+                info!("Using cached standards from {}", cache_path.display());
+                return Ok((cache.standards, cache.event_orders, cache.age_labels.into_iter().collect()));
+            }
+            debug!("Standards cache {} is out of date", cache_path.display());
+        }
+    }
+
+    let (standards, event_orders, age_labels) =
+        load_time_standards_labeled(standards_file, gender_tabs, columns, stroke_names, required_genders)?;
+
+    if let Some((modified_secs, modified_nanos)) = modified {
+        let cache = StandardsCache {
+            modified_secs,
+            modified_nanos,
+            gender_tabs: gender_tabs.clone(),
+            columns,
+            stroke_names: stroke_names.clone(),
+            required_genders: required_genders.to_vec(),
+            standards,
+            event_orders,
+            age_labels: age_labels.into_iter().collect(),
+        };
+        match write_cache(&cache_path, &cache) {
+            Ok(()) => debug!("Wrote standards cache {}", cache_path.display()),
+            Err(e) => warn!("Could not write standards cache {}: {}", cache_path.display(), e),
+        }
+        return Ok((cache.standards, cache.event_orders, cache.age_labels.into_iter().collect()));
+    }
+
+    Ok((standards, event_orders, age_labels))
+}
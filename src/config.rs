@@ -0,0 +1,531 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Deserializer};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::course::Course;
+use crate::error::AnalysisError;
+use crate::normalize::{default_stroke_names, normalize_any_event_with, EventNormalizer};
+use crate::standards::StandardsColumns;
+
+// *** CONFIGURATION ***
+// Defaults used when neither a flag nor the config file sets a value
+pub const TIME_STANDARDS_FILE: &str = "timestandards.xlsx";
+pub const DATA_FOLDER: &str = "data";
+pub const OUTPUT_FILE: &str = "qualifier_counts.xlsx";
+pub const TEAM_PREFIX: &str = "CAN-MBSK_";
+pub const TIME_COLUMN: usize = 9; // Column J
+pub const NAME_COLUMN: usize = 4; // Column E
+pub const NEAR_MISS_PCT: f64 = 2.0;
+pub const TOP_N: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Xlsx,
+    Json,
+    Markdown,
+}
+
+// Shape of the count sheets in the xlsx output: `wide` grids with an age
+// group per column, or `long` with one row per count for pivoting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Layout {
+    #[default]
+    Wide,
+    Long,
+}
+
+// What the first count grid of each gender sheet shows. `swims` (the default,
+// as before) counts every qualifying swim, so an athlete who made the cut twice
+// in an event counts twice; `athletes` counts each athlete once per event by
+// best time. The other metric follows in the second grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum CountMode {
+    #[default]
+    Swims,
+    Athletes,
+}
+
+// Row order of the events in the output grids
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum EventSort {
+    // Order of column A in the standards workbook
+    #[default]
+    Standards,
+    // By normalized name ("100Bk" before "50Fr")
+    Alpha,
+    // Free, back, breast, fly, IM, then by distance; relays last
+    StrokeDistance,
+}
+
+// What to do with results older than every age group in the standards
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum OverAgePolicy {
+    // Count them against the oldest age group
+    #[default]
+    Clamp,
+    // Leave them out of every count
+    Drop,
+    // Stop the run
+    Error,
+}
+
+// Which time counts when a sheet has both a final and a prelim time (prelim_col)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum RoundPolicy {
+    // The faster of the two; a tie goes to the final
+    #[default]
+    Best,
+    // The final time, or the prelim when there's no final
+    Final,
+    // The prelim time, or the final when there's no prelim
+    Prelim,
+}
+
+// Run settings loaded from config.toml
+// Required: standards, data (one folder or a list)
+// Optional: output, team_prefixes, time_col, name_col, split_col, time_headers,
+//           name_headers, event_headers, near_miss_pct, format, layout,
+//           standards_course, gender_tabs, autofit, highlight, reorder_names,
+//           events, min_age, max_age, cache_standards, stroke_names, dedup,
+//           top_n, per_meet, since, until, ignore_sheets, required_genders,
+//           best_vs_cut, event_sort, strict, previous_standards, over_age,
+//           append, tolerance, standards_event_col, standards_age_col,
+//           output_dir, split_by_gender, sex_codes, combine_ages,
+//           combine_ages_only, events_from_meets, count_mode, prelim_col,
+//           round_policy, roster_csv
+//           (defaults above)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub standards: PathBuf,
+    // Folders scanned for meet files, e.g. one per course
+    #[serde(deserialize_with = "one_or_many")]
+    pub data: Vec<PathBuf>,
+    #[serde(default = "default_output")]
+    pub output: PathBuf,
+    // Meet files are picked up when their name starts with any of these.
+    // Accepts a single string or a list; `team_prefix` is the older key.
+    #[serde(default = "default_team_prefixes", alias = "team_prefix",
+            deserialize_with = "one_or_many")]
+    pub team_prefixes: Vec<String>,
+    // Fixed time/name columns, 0-based or a letter ("J"). When unset, the
+    // header row is searched and TIME_COLUMN/NAME_COLUMN are the fallback.
+    #[serde(default, deserialize_with = "column_index")]
+    pub time_col: Option<usize>,
+    #[serde(default, deserialize_with = "column_index")]
+    pub name_col: Option<usize>,
+    // Column holding the first 50 split. Off unless set; when set, a split
+    // that makes the 50 standard of the same stroke counts as a qualifying swim.
+    #[serde(default, deserialize_with = "column_index")]
+    pub split_col: Option<usize>,
+    // Column holding the prelim time, for sheets with a prelim and a final
+    // column (the time column is the final). Off unless set.
+    #[serde(default, deserialize_with = "column_index")]
+    pub prelim_col: Option<usize>,
+    // Which of the two times a result uses when both were swum
+    #[serde(default)]
+    pub round_policy: RoundPolicy,
+    // Header labels that identify the time/name/event columns (case-insensitive)
+    #[serde(default = "default_time_headers")]
+    pub time_headers: Vec<String>,
+    #[serde(default = "default_name_headers")]
+    pub name_headers: Vec<String>,
+    #[serde(default = "default_event_headers")]
+    pub event_headers: Vec<String>,
+    // Percent over the standard still reported as a near miss
+    #[serde(default = "default_near_miss_pct")]
+    pub near_miss_pct: f64,
+    #[serde(default)]
+    pub format: OutputFormat,
+    #[serde(default)]
+    pub layout: Layout,
+    // Metric of the main grid in the wide layout: swims or athletes
+    #[serde(default)]
+    pub count_mode: CountMode,
+    // Course the standards workbook is published for; meet results from other
+    // courses are converted to it before comparing
+    #[serde(default)]
+    pub standards_course: Course,
+    // Standards workbook tab -> gender key used in meet filenames
+    #[serde(default = "default_gender_tabs")]
+    pub gender_tabs: HashMap<String, String>,
+    // Fit column widths to their contents and freeze the header row/column in
+    // the xlsx output; turn off when post-processing the file
+    #[serde(default = "default_autofit")]
+    pub autofit: bool,
+    // Rewrite "Last, First" athlete names as "First Last" so both spellings
+    // count as one athlete
+    #[serde(default = "default_reorder_names")]
+    pub reorder_names: bool,
+    // Shade the count grids with a color scale (darker = more qualifiers)
+    #[serde(default = "default_highlight")]
+    pub highlight: bool,
+    // Normalized event names ("50Bu", "200Me") to analyze; empty means all
+    #[serde(default)]
+    pub events: Vec<String>,
+    // Inclusive age range for swimmers and output columns; unset means no limit
+    #[serde(default)]
+    pub min_age: Option<i32>,
+    #[serde(default)]
+    pub max_age: Option<i32>,
+    // Reuse parsed standards from <standards>.cache.json while the workbook
+    // is unchanged
+    #[serde(default = "default_cache_standards")]
+    pub cache_standards: bool,
+    // Stroke name -> abbreviation used to normalize event names in the meet
+    // files and standards ("Butterfly" -> "Bu"); longest names match first
+    #[serde(default = "default_stroke_names")]
+    pub stroke_names: HashMap<String, String>,
+    // Drop repeated (name, event, time) rows within a meet sheet instead of
+    // only warning about them
+    #[serde(default)]
+    pub dedup: bool,
+    // Fastest athletes listed per sex/age/event on the Rankings sheet
+    #[serde(default = "default_top_n")]
+    pub top_n: usize,
+    // Write one output file per meet (<output>_<meet>.xlsx) instead of
+    // pooling every meet file into one
+    #[serde(default)]
+    pub per_meet: bool,
+    // Inclusive meet date range ("2024-09-01"); files without a date in their
+    // name are skipped while either end is set
+    #[serde(default)]
+    pub since: Option<NaiveDate>,
+    #[serde(default)]
+    pub until: Option<NaiveDate>,
+    // Meet workbook sheets to skip quietly ("Summary", "Info"); case-insensitive.
+    // Other sheets that aren't event names are skipped with a message.
+    #[serde(default)]
+    pub ignore_sheets: Vec<String>,
+    // Genders the standards workbook must have a tab for; the run stops with
+    // a list of problems otherwise
+    #[serde(default = "default_required_genders")]
+    pub required_genders: Vec<String>,
+    // Add a "Best Times vs Cut" sheet: the fastest swim per age group/event
+    // and its gap to the standard, including events nobody qualified in
+    #[serde(default)]
+    pub best_vs_cut: bool,
+    // Row order of the events in the output: standards, alpha or stroke-distance
+    #[serde(default)]
+    pub event_sort: EventSort,
+    // Fail the run (nonzero exit) when any warning was recorded, for checking
+    // a data set that should be clean; the output is still written
+    #[serde(default)]
+    pub strict: bool,
+    // Last season's standards workbook; when set, a "Changed Status" sheet
+    // lists athletes who qualify under only one of the two
+    #[serde(default)]
+    pub previous_standards: Option<PathBuf>,
+    // Results older than every age group (a 17-year-old against a 15&O cut):
+    // clamp to the oldest group, drop them, or stop with an error
+    #[serde(default)]
+    pub over_age: OverAgePolicy,
+    // Keep the "Count History" sheet of the existing output and add a column
+    // with this run's qualifying swims (xlsx only)
+    #[serde(default)]
+    pub append: bool,
+    // Seconds a swim may be over a cut and still make it (0 = exact cuts)
+    #[serde(default)]
+    pub tolerance: f64,
+    // Standards tab columns, 0-based or a letter, for templates that don't
+    // have the events in A and the age groups from B; see standards_columns
+    #[serde(default, deserialize_with = "column_index")]
+    pub standards_event_col: Option<usize>,
+    #[serde(default, deserialize_with = "column_index")]
+    pub standards_age_col: Option<usize>,
+    // Folder the output file (and warnings.json) is written to, created if
+    // missing; output's file name is kept
+    #[serde(default)]
+    pub output_dir: Option<PathBuf>,
+    // CSV of the qualified athletes (name, sex, age group, events) for
+    // registration systems; not written unless set
+    #[serde(default)]
+    pub roster_csv: Option<PathBuf>,
+    // Write one file per gender (mens.xlsx, womens.xlsx) next to the output
+    // instead of one workbook with a sheet per gender
+    #[serde(default)]
+    pub split_by_gender: bool,
+    // Custom meet event normalization, set from code (not TOML); None uses
+    // the built-in rules with stroke_names. See EventNormalizer.
+    #[serde(skip)]
+    pub event_normalizer: Option<EventNormalizer>,
+    // Sex field of the meet filenames -> gender key of the standards
+    // (case-insensitive); codes not listed are kept as written
+    #[serde(default = "default_sex_codes")]
+    pub sex_codes: HashMap<String, String>,
+    // Add an "All Ages" column summing each event over the age groups; with
+    // combine_ages_only it replaces the age group columns
+    #[serde(default)]
+    pub combine_ages: bool,
+    #[serde(default)]
+    pub combine_ages_only: bool,
+    // Also list events swum at the meets that have no standard, after the
+    // standards' events, marked "no standard"
+    #[serde(default)]
+    pub events_from_meets: bool,
+}
+
+fn default_output() -> PathBuf {
+    PathBuf::from(OUTPUT_FILE)
+}
+
+fn default_team_prefixes() -> Vec<String> {
+    vec![TEAM_PREFIX.to_string()]
+}
+
+// "Mens"/"Womens" are the original tab names; the rest cover common variants
+fn default_gender_tabs() -> HashMap<String, String> {
+    [
+        ("Mens", "Men"),
+        ("Womens", "Women"),
+        ("Men", "Men"),
+        ("Women", "Women"),
+        ("Boys", "Men"),
+        ("Girls", "Women"),
+        ("Mixed", "Mixed"),
+    ]
+    .into_iter()
+    .map(|(tab, gender)| (tab.to_string(), gender.to_string()))
+    .collect()
+}
+
+// Letters and words used for the sex field (CAN-MBSK_2024_SCY_B_11-12.xlsx)
+fn default_sex_codes() -> HashMap<String, String> {
+    [
+        ("M", "Men"),
+        ("B", "Men"),
+        ("Male", "Men"),
+        ("Boys", "Men"),
+        ("Mens", "Men"),
+        ("F", "Women"),
+        ("G", "Women"),
+        ("W", "Women"),
+        ("Female", "Women"),
+        ("Girls", "Women"),
+        ("Womens", "Women"),
+        ("X", "Mixed"),
+    ]
+    .into_iter()
+    .map(|(code, gender)| (code.to_string(), gender.to_string()))
+    .collect()
+}
+
+fn default_required_genders() -> Vec<String> {
+    vec!["Men".to_string(), "Women".to_string()]
+}
+
+fn one_or_many<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany<T> {
+        One(T),
+        Many(Vec<T>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(s) => vec![s],
+        OneOrMany::Many(v) => v,
+    })
+}
+
+// Spreadsheet column: a 0-based index ("9") or letters ("J", "AB")
+pub fn parse_column(column: &str) -> Option<usize> {
+    let column = column.trim();
+    if let Ok(index) = column.parse::<usize>() {
+        return Some(index);
+    }
+    if column.is_empty() || !column.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    // Letters are bijective base 26: A = 1 ... Z = 26, AA = 27
+    let number = column.to_ascii_uppercase().bytes()
+        .try_fold(0usize, |acc, b| acc.checked_mul(26)?.checked_add((b - b'A' + 1) as usize))?;
+    Some(number - 1)
+}
+
+// 0-based column index as letters, the inverse of parse_column: 9 -> "J"
+pub fn column_letter(index: usize) -> String {
+    let mut letters = Vec::new();
+    let mut number = index + 1;
+    while number > 0 {
+        let rem = (number - 1) % 26;
+        letters.push(b'A' + rem as u8);
+        number = (number - 1) / 26;
+    }
+    letters.reverse();
+    String::from_utf8(letters).expect("ASCII letters")
+}
+
+fn column_index<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<usize>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Column {
+        Index(usize),
+        Letter(String),
+    }
+
+    match Column::deserialize(deserializer)? {
+        Column::Index(index) => Ok(Some(index)),
+        Column::Letter(letter) => parse_column(&letter).map(Some).ok_or_else(|| {
+            serde::de::Error::custom(format!("invalid column '{}' (expected an index or letters like J)", letter))
+        }),
+    }
+}
+
+fn default_near_miss_pct() -> f64 {
+    NEAR_MISS_PCT
+}
+
+fn default_top_n() -> usize {
+    TOP_N
+}
+
+fn default_autofit() -> bool {
+    true
+}
+
+fn default_highlight() -> bool {
+    true
+}
+
+fn default_reorder_names() -> bool {
+    true
+}
+
+fn default_cache_standards() -> bool {
+    true
+}
+
+fn default_time_headers() -> Vec<String> {
+    vec!["Time".to_string(), "Final Time".to_string()]
+}
+
+fn default_name_headers() -> Vec<String> {
+    vec!["Name".to_string(), "Athlete".to_string()]
+}
+
+fn default_event_headers() -> Vec<String> {
+    vec!["Event".to_string()]
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            standards: PathBuf::from(TIME_STANDARDS_FILE),
+            data: vec![PathBuf::from(DATA_FOLDER)],
+            output: default_output(),
+            team_prefixes: default_team_prefixes(),
+            time_col: None,
+            name_col: None,
+            split_col: None,
+            prelim_col: None,
+            round_policy: RoundPolicy::default(),
+            time_headers: default_time_headers(),
+            name_headers: default_name_headers(),
+            event_headers: default_event_headers(),
+            near_miss_pct: default_near_miss_pct(),
+            format: OutputFormat::default(),
+            layout: Layout::default(),
+            count_mode: CountMode::default(),
+            standards_course: Course::default(),
+            gender_tabs: default_gender_tabs(),
+            autofit: default_autofit(),
+            highlight: default_highlight(),
+            reorder_names: default_reorder_names(),
+            events: Vec::new(),
+            min_age: None,
+            max_age: None,
+            cache_standards: default_cache_standards(),
+            stroke_names: default_stroke_names(),
+            dedup: false,
+            top_n: default_top_n(),
+            per_meet: false,
+            since: None,
+            until: None,
+            ignore_sheets: Vec::new(),
+            required_genders: default_required_genders(),
+            best_vs_cut: false,
+            event_sort: EventSort::default(),
+            strict: false,
+            previous_standards: None,
+            over_age: OverAgePolicy::default(),
+            append: false,
+            tolerance: 0.0,
+            standards_event_col: None,
+            standards_age_col: None,
+            output_dir: None,
+            roster_csv: None,
+            split_by_gender: false,
+            event_normalizer: None,
+            sex_codes: default_sex_codes(),
+            combine_ages: false,
+            combine_ages_only: false,
+            events_from_meets: false,
+        }
+    }
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self, AnalysisError> {
+        let text = std::fs::read_to_string(path).map_err(|e| AnalysisError::Config {
+            path: path.to_path_buf(),
+            reason: e.to_string(),
+        })?;
+        toml::from_str(&text).map_err(|e| AnalysisError::Config {
+            path: path.to_path_buf(),
+            reason: e.to_string(),
+        })
+    }
+
+    // Whether a meet date falls inside the since/until range
+    pub fn includes_date(&self, date: NaiveDate) -> bool {
+        self.since.is_none_or(|since| date >= since) && self.until.is_none_or(|until| date <= until)
+    }
+
+    // Whether a meet sheet is on the ignore_sheets list
+    pub fn ignores_sheet(&self, sheet_name: &str) -> bool {
+        self.ignore_sheets.iter().any(|s| s.trim().eq_ignore_ascii_case(sheet_name.trim()))
+    }
+
+    // The gender key for a meet filename's sex field ("B" -> "Men"), or the
+    // field itself when sex_codes doesn't list it
+    pub fn map_sex(&self, code: &str) -> String {
+        self.sex_codes.iter()
+            .find(|(c, _)| c.eq_ignore_ascii_case(code.trim()))
+            .map_or_else(|| code.to_string(), |(_, gender)| gender.clone())
+    }
+
+    // Whether a normalized event passes the `events` filter
+    pub fn includes_event(&self, event: &str) -> bool {
+        self.events.is_empty() || self.events.iter().any(|e| e.eq_ignore_ascii_case(event))
+    }
+
+    // A meet sheet name (or Event cell) as a normalized event and whether it's
+    // a relay, through event_normalizer when one is set
+    pub fn normalize_event(&self, name: &str) -> Option<(String, bool)> {
+        match &self.event_normalizer {
+            Some(normalizer) => normalizer.normalize(name),
+            None => normalize_any_event_with(name, &self.stroke_names),
+        }
+    }
+
+    // Standards tab layout: events in column A unless standards_event_col is
+    // set, age groups from the column after the events unless standards_age_col
+    // is set
+    pub fn standards_columns(&self) -> StandardsColumns {
+        let event = self.standards_event_col.unwrap_or(StandardsColumns::default().event);
+        StandardsColumns { event, first_age: self.standards_age_col.unwrap_or(event + 1) }
+    }
+}
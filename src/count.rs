@@ -0,0 +1,917 @@
+use log::debug;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::age::{age_group_for, age_sort_key, match_age_group, parse_age};
+use crate::config::EventSort;
+use crate::course::{convert_event, convert_time, Course};
+use crate::meet::MeetResult;
+use crate::normalize::{sort_events, split_event};
+use crate::standards::{
+    highest_cut_level, makes_cut, qualifying_time, AgeGroupStandards, CutLevelKey, CutTimes, EventStandards,
+    StandardKey,
+};
+
+// An athlete's best time in an event next to the standard for their age group
+#[derive(Debug, Clone)]
+pub struct StandardComparison {
+    pub name: String,
+    pub sex: String,
+    pub age_group: String,
+    pub event: String,
+    pub time: f64,
+    pub standard: f64,
+}
+
+impl StandardComparison {
+    // Seconds slower than the standard (negative when under the cut)
+    pub fn gap(&self) -> f64 {
+        self.time - self.standard
+    }
+
+    // Seconds under the standard: positive for a qualifier, negative for a
+    // near miss
+    pub fn margin(&self) -> f64 {
+        self.standard - self.time
+    }
+
+    // Time as a percent of the standard (99.2 is just under the cut), comparable
+    // across events of any length. None without a usable standard.
+    pub fn pct_of_standard(&self) -> Option<f64> {
+        if self.standard > 0.0 && self.standard.is_finite() {
+            Some(self.time / self.standard * 100.0)
+        } else {
+            None
+        }
+    }
+}
+
+// An athlete's place on an event leaderboard, with whether their best time
+// made the standard for their age group
+#[derive(Debug, Clone)]
+pub struct Ranking {
+    pub rank: usize,
+    pub name: String,
+    pub sex: String,
+    pub age: String,
+    pub event: String,
+    pub time: f64,
+    pub qualified: bool,
+}
+
+// Fastest swim found for one standards age group and event, next to its cut.
+// name and time are None when nobody swam it.
+#[derive(Debug, Clone)]
+pub struct BestVsCut {
+    pub sex: String,
+    pub age_group: String,
+    pub event: String,
+    pub standard: f64,
+    pub name: Option<String>,
+    pub time: Option<f64>,
+}
+
+impl BestVsCut {
+    // Seconds slower than the standard (negative when under the cut)
+    pub fn gap(&self) -> Option<f64> {
+        self.time.map(|time| time - self.standard)
+    }
+}
+
+// An athlete whose best time qualifies under one standards file but not the
+// other. A standard is None when that file has no cut for the event/age group.
+#[derive(Debug, Clone)]
+pub struct StatusChange {
+    pub name: String,
+    pub sex: String,
+    pub age_group: String,
+    pub event: String,
+    pub time: f64,
+    pub previous_standard: Option<f64>,
+    pub standard: Option<f64>,
+    pub newly_qualified: bool, // Qualifies under the current standards (and so not under the previous ones)
+}
+
+// A swim under the standard of its matched age group whose exact age has no
+// standard. count_unique_qualifiers counts it (matched age) but
+// count_qualifiers doesn't (exact age), so it explains a gap between them.
+#[derive(Debug, Clone)]
+pub struct AgeMismatch {
+    pub name: String,
+    pub sex: String,
+    pub age: String, // Entry age from the meet filename
+    pub age_group: String, // Age group it matched
+    pub event: String,
+    pub time: f64,
+    pub standard: f64,
+}
+
+// An athlete who qualified in more than one event, for the Multi-Event
+// Qualifiers sheet
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiEventQualifier {
+    pub name: String,
+    pub sex: String,
+    pub age_group: String, // Matched age group, as in count_unique_qualifiers
+    pub events: usize,
+}
+
+// One qualified athlete and every event they made the cut in, for --roster-csv
+#[derive(Debug, Clone, PartialEq)]
+pub struct RosterEntry {
+    pub name: String,
+    pub sex: String,
+    pub age_group: String, // Matched age group, as in count_unique_qualifiers
+    pub events: Vec<String>, // In the gender's standards order
+}
+
+// A (sex, event, age) seen in the meet data with no standard to compare against
+#[derive(Debug, Clone)]
+pub struct UnmatchedKey {
+    pub sex: String,
+    pub event: String,
+    pub age: String,
+    pub results: usize, // Results dropped for this key
+}
+
+// How many athletes swam an event at an entry age, qualifying or not, for the
+// Participation sheet
+#[derive(Debug, Clone, PartialEq)]
+pub struct Participation {
+    pub sex: String,
+    pub age: String, // Entry age from the meet filename, "13-14" for a range file
+    pub event: String,
+    pub athletes: usize, // Distinct names
+    pub swims: usize,
+}
+
+// Re-expresses results in the standards' course so times compare like for
+// like: every count takes results converted here, once per run. `course` keeps
+// the pool each swim was swum in (the per-course count sheets group on it), so
+// converting twice would convert twice. Results with an unrecognized course
+// code are kept as recorded.
+pub fn to_standards_course(meet_results: &mut [MeetResult], standards_course: Course) {
+    let mut unknown_course = 0;
+    
+    for result in meet_results.iter_mut() {
+        match Course::from_code(&result.course) {
+            Some(course) => {
+                result.split_time = result.split_time.zip(split_event(&result.event))
+                    .map(|(split, event)| convert_time(split, course, standards_course, &event));
+                result.time = convert_time(result.time, course, standards_course, &result.event);
+                result.event = convert_event(&result.event, course, standards_course).into();
+            }
+            None => unknown_course += 1,
+        }
+    }
+    
+    if unknown_course > 0 {
+        debug!("{} results had an unknown course and were not converted", unknown_course);
+    }
+}
+
+// Cut times for an exact age: its own age group, or a range ("11-12")
+// containing it
+fn cuts_for_age<'a>(
+    event_standards: &'a AgeGroupStandards,
+    age: &str,
+) -> Option<(&'a String, &'a CutTimes)> {
+    let age_group = age_group_for(age, event_standards.keys())?;
+    event_standards.get_key_value(age_group)
+}
+
+// Age group the result counts under (see match_age_group)
+fn result_age_group(
+    result: &MeetResult,
+    standards: &HashMap<String, EventStandards>,
+) -> Option<String> {
+    match_age_group(&result.age_min, &result.age, &result.event, standards.get(result.sex())?)
+}
+
+// Standard for the result's best matching age group
+fn matched_standard(
+    result: &MeetResult,
+    standards: &HashMap<String, EventStandards>,
+) -> Option<(String, f64)> {
+    let event_standards = standards.get(result.sex())?.get(result.event())?;
+    let matched_age = result_age_group(result, standards)?;
+    let qualifying_time = qualifying_time(event_standards.get(&matched_age)?)?;
+    Some((matched_age, qualifying_time))
+}
+
+// Qualifying swims per (sex, age group, event). A swim up to `tolerance`
+// seconds over the cut still qualifies (see makes_cut).
+pub fn count_qualifiers(
+    meet_results: &[MeetResult],
+    standards: &HashMap<String, EventStandards>,
+    tolerance: f64,
+) -> HashMap<StandardKey, usize> {
+    let mut qualifier_counts: HashMap<StandardKey, usize> = HashMap::new();
+    let mut matches_found = 0;
+    let mut no_standard_count = 0;
+    
+    // Each relay row is one team entry, so relays are counted per team and
+    // compared against the relay standard for the same normalized event
+    for result in meet_results {
+        // Get standards for this gender
+        if let Some(gender_standards) = standards.get(result.sex()) {
+            // Get standards for this event
+            if let Some(event_standards) = gender_standards.get(result.event()) {
+                // Check if there's a qualifying time for this age (or a range containing it)
+                let matched = cuts_for_age(event_standards, &result.age)
+                    .and_then(|(age_group, cuts)| Some((age_group, qualifying_time(cuts)?)));
+                if let Some((age_group, qualifying_time)) = matched {
+                    if makes_cut(result.time, qualifying_time, tolerance) {
+                        let key = (result.sex().to_string(), age_group.clone(), result.event().to_string());
+                        *qualifier_counts.entry(key).or_insert(0) += 1;
+                        matches_found += 1;
+                    }
+                } else {
+                    no_standard_count += 1;
+                }
+            }
+        }
+    }
+    
+    // With a split column, a split that makes the same stroke's 50 standard
+    // counts as a qualifying 50 even when the full swim doesn't qualify
+    let mut splits_found = 0;
+    for result in meet_results {
+        let (split_time, event) = match result.split_time.zip(split_event(&result.event)) {
+            Some(split) => split,
+            None => continue,
+        };
+        let matched = standards.get(result.sex())
+            .and_then(|gender_standards| gender_standards.get(&event))
+            .and_then(|event_standards| cuts_for_age(event_standards, &result.age))
+            .and_then(|(age_group, cuts)| Some((age_group, qualifying_time(cuts)?)));
+        if let Some((age_group, qualifying_time)) = matched {
+            if makes_cut(split_time, qualifying_time, tolerance) {
+                let key = (result.sex().to_string(), age_group.clone(), event);
+                *qualifier_counts.entry(key).or_insert(0) += 1;
+                splits_found += 1;
+            }
+        }
+    }
+    
+    debug!("Found {} qualifying times", matches_found);
+    if splits_found > 0 {
+        debug!("Found {} qualifying splits", splits_found);
+    }
+    debug!("{} results had no matching standard", no_standard_count);
+    
+    qualifier_counts
+}
+
+// Qualifying swims per cut level, each swim counted once at the fastest level
+// it made (a AAA swim isn't also counted as AA). Ages match their own age
+// group or a range containing them, as in count_qualifiers.
+pub fn count_cut_levels(
+    meet_results: &[MeetResult],
+    standards: &HashMap<String, EventStandards>,
+    tolerance: f64,
+) -> HashMap<CutLevelKey, usize> {
+    let mut level_counts: HashMap<CutLevelKey, usize> = HashMap::new();
+    
+    for result in meet_results {
+        let matched = standards.get(result.sex())
+            .and_then(|gender_standards| gender_standards.get(result.event()))
+            .and_then(|event_standards| cuts_for_age(event_standards, &result.age));
+        
+        let level = matched
+            .and_then(|(age_group, cuts)| Some((age_group, highest_cut_level(result.time, cuts, tolerance)?)));
+        if let Some((age_group, level)) = level {
+            let key = (result.sex().to_string(), age_group.clone(), result.event().to_string(),
+                       level.to_string());
+            *level_counts.entry(key).or_insert(0) += 1;
+        }
+    }
+    
+    level_counts
+}
+
+// Like count_cut_levels, but each athlete is classified once per event by
+// their best time
+pub fn count_best_time_cut_levels(
+    meet_results: &[MeetResult],
+    standards: &HashMap<String, EventStandards>,
+    tolerance: f64,
+) -> HashMap<CutLevelKey, usize> {
+    count_cut_levels(&best_times(meet_results), standards, tolerance)
+}
+
+// A blank or whitespace-only name can't be attributed to an athlete. The
+// parsers already trim names, but results can also come from the library API.
+fn has_athlete_name(result: &MeetResult) -> bool {
+    !result.name.trim().is_empty()
+}
+
+// Keeps only the fastest swim per (name, sex, age, event). Results without a
+// name can't be attributed to an athlete and are dropped.
+pub fn best_times(meet_results: &[MeetResult]) -> Vec<MeetResult> {
+    let mut best: HashMap<(String, String, String, String), MeetResult> = HashMap::new();
+    
+    for result in meet_results {
+        if !has_athlete_name(result) {
+            continue;
+        }
+        
+        let key = (result.name.clone(), result.sex().to_string(), result.age().to_string(),
+                   result.event().to_string());
+        match best.get(&key) {
+            Some(existing) if existing.time <= result.time => {}
+            _ => {
+                best.insert(key, result.clone());
+            }
+        }
+    }
+    
+    best.into_values().collect()
+}
+
+// Like count_qualifiers, but an athlete who beat the cut several times in the
+// same event is counted once. With the results already converted, a 500 SCY
+// and a 400 LCM are the same event.
+pub fn count_best_time_qualifiers(
+    meet_results: &[MeetResult],
+    standards: &HashMap<String, EventStandards>,
+    tolerance: f64,
+) -> HashMap<StandardKey, usize> {
+    count_qualifiers(&best_times(meet_results), standards, tolerance)
+}
+
+// Athletes whose best time missed the standard (allowing `tolerance`) by no
+// more than threshold_pct percent of it, sorted by sex, age group, event, then
+// smallest gap
+pub fn count_near_qualifiers(
+    meet_results: &[MeetResult],
+    standards: &HashMap<String, EventStandards>,
+    tolerance: f64,
+    threshold_pct: f64,
+) -> Vec<StandardComparison> {
+    let mut near_misses = Vec::new();
+    
+    for result in best_times(meet_results) {
+        if result.is_relay {
+            continue;
+        }
+        
+        if let Some((age_group, standard)) = matched_standard(&result, standards) {
+            let limit = standard * (1.0 + threshold_pct / 100.0);
+            if !makes_cut(result.time, standard, tolerance) && result.time <= limit {
+                near_misses.push(StandardComparison {
+                    name: result.name.clone(),
+                    sex: result.sex().to_string(),
+                    age_group,
+                    event: result.event().to_string(),
+                    time: result.time,
+                    standard,
+                });
+            }
+        }
+    }
+    
+    near_misses.sort_by(|a, b| {
+        a.sex.cmp(&b.sex)
+            .then_with(|| age_sort_key(&a.age_group).cmp(&age_sort_key(&b.age_group)))
+            .then_with(|| a.event.cmp(&b.event))
+            .then_with(|| a.gap().total_cmp(&b.gap()))
+    });
+    
+    near_misses
+}
+
+// Every qualifying athlete once per (name, age group, event) with their best
+// time and the standard they beat. Sorted by sex, age group, event, then time.
+pub fn qualifier_details(
+    meet_results: &[MeetResult],
+    standards: &HashMap<String, EventStandards>,
+    tolerance: f64,
+) -> Vec<StandardComparison> {
+    let mut details: HashMap<(String, String, String, String), StandardComparison> = HashMap::new();
+    
+    for result in best_times(meet_results) {
+        if result.is_relay {
+            continue;
+        }
+        
+        if let Some((age_group, standard)) = matched_standard(&result, standards) {
+            if !makes_cut(result.time, standard, tolerance) {
+                continue;
+            }
+            
+            // Two entry ages can share an age group; keep the faster swim
+            let key = (result.name.clone(), result.sex().to_string(), age_group.clone(),
+                       result.event().to_string());
+            match details.get(&key) {
+                Some(existing) if existing.time <= result.time => {}
+                _ => {
+                    details.insert(key, StandardComparison {
+                        name: result.name.clone(),
+                        sex: result.sex().to_string(),
+                        age_group,
+                        event: result.event().to_string(),
+                        time: result.time,
+                        standard,
+                    });
+                }
+            }
+        }
+    }
+    
+    let mut details: Vec<StandardComparison> = details.into_values().collect();
+    details.sort_by(|a, b| {
+        a.sex.cmp(&b.sex)
+            .then_with(|| age_sort_key(&a.age_group).cmp(&age_sort_key(&b.age_group)))
+            .then_with(|| a.event.cmp(&b.event))
+            .then_with(|| a.time.total_cmp(&b.time))
+    });
+    
+    details
+}
+
+// The n fastest athletes by best time per (sex, age, event), as (rank, best
+// swim). Tied times share a rank and are all listed, so a group can run past
+// n; the next rank skips the tied places (1, 2, 2, 4). Relays are left out.
+// Sorted by sex, age, event, then rank.
+pub fn top_n_per_event(meet_results: &[MeetResult], n: usize) -> Vec<(usize, MeetResult)> {
+    let mut groups: HashMap<(String, String, String), Vec<MeetResult>> = HashMap::new();
+    for result in best_times(meet_results) {
+        if result.is_relay {
+            continue;
+        }
+        let key = (result.sex().to_string(), result.age().to_string(), result.event().to_string());
+        groups.entry(key).or_default().push(result);
+    }
+    
+    let mut keys: Vec<_> = groups.keys().cloned().collect();
+    keys.sort_by(|a, b| {
+        a.0.cmp(&b.0)
+            .then_with(|| age_sort_key(&a.1).cmp(&age_sort_key(&b.1)))
+            .then_with(|| a.2.cmp(&b.2))
+    });
+    
+    let mut ranked = Vec::new();
+    for key in keys {
+        let mut group = groups.remove(&key).unwrap_or_default();
+        group.sort_by(|a, b| a.time.total_cmp(&b.time).then_with(|| a.name.cmp(&b.name)));
+        
+        let mut rank = 0;
+        let mut previous_time = None;
+        for (place, result) in group.into_iter().enumerate() {
+            if previous_time != Some(result.time) {
+                rank = place + 1;
+            }
+            if rank > n {
+                break;
+            }
+            previous_time = Some(result.time);
+            ranked.push((rank, result));
+        }
+    }
+    
+    ranked
+}
+
+// top_n_per_event, flagging athletes whose best time made the standard for
+// their age group
+pub fn rankings(
+    meet_results: &[MeetResult],
+    standards: &HashMap<String, EventStandards>,
+    tolerance: f64,
+    n: usize,
+) -> Vec<Ranking> {
+    top_n_per_event(meet_results, n)
+        .into_iter()
+        .map(|(rank, result)| {
+            let qualified = matched_standard(&result, standards)
+                .is_some_and(|(_, standard)| makes_cut(result.time, standard, tolerance));
+            Ranking {
+                rank,
+                name: result.name.clone(),
+                sex: result.sex().to_string(),
+                age: result.age().to_string(),
+                event: result.event().to_string(),
+                time: result.time,
+                qualified,
+            }
+        })
+        .collect()
+}
+
+// One row per (sex, age group, event) in the standards with the fastest swim
+// matched to it, whether or not anyone qualified. Sorted by sex, age group,
+// then event.
+pub fn best_vs_cut(
+    meet_results: &[MeetResult],
+    standards: &HashMap<String, EventStandards>,
+) -> Vec<BestVsCut> {
+    let mut rows: HashMap<(String, String, String), BestVsCut> = HashMap::new();
+    
+    for (sex, gender_standards) in standards {
+        for (event, event_standards) in gender_standards {
+            for (age_group, cuts) in event_standards {
+                if let Some(standard) = qualifying_time(cuts) {
+                    let key = (sex.clone(), age_group.clone(), event.clone());
+                    rows.insert(key, BestVsCut {
+                        sex: sex.clone(),
+                        age_group: age_group.clone(),
+                        event: event.clone(),
+                        standard,
+                        name: None,
+                        time: None,
+                    });
+                }
+            }
+        }
+    }
+    
+    for result in best_times(meet_results) {
+        let age_group = match matched_standard(&result, standards) {
+            Some((age_group, _)) => age_group,
+            None => continue,
+        };
+        let key = (result.sex().to_string(), age_group, result.event().to_string());
+        if let Some(row) = rows.get_mut(&key) {
+            if row.time.is_none_or(|best| result.time < best) {
+                row.time = Some(result.time);
+                row.name = Some(result.name.clone());
+            }
+        }
+    }
+    
+    let mut rows: Vec<BestVsCut> = rows.into_values().collect();
+    rows.sort_by(|a, b| {
+        a.sex.cmp(&b.sex)
+            .then_with(|| age_sort_key(&a.age_group).cmp(&age_sort_key(&b.age_group)))
+            .then_with(|| a.event.cmp(&b.event))
+    });
+    
+    rows
+}
+
+// Keys that count_qualifiers drops: no standards for the gender or event, or
+// no age group for the exact age. Sorted by sex, event, then age.
+pub fn unmatched_results(
+    meet_results: &[MeetResult],
+    standards: &HashMap<String, EventStandards>,
+) -> Vec<UnmatchedKey> {
+    let mut dropped: HashMap<(String, String, String), usize> = HashMap::new();
+    
+    for result in meet_results {
+        let matched = standards.get(result.sex())
+            .and_then(|gender_standards| gender_standards.get(result.event()))
+            .is_some_and(|event_standards| cuts_for_age(event_standards, &result.age).is_some());
+        
+        if !matched {
+            let key = (result.sex().to_string(), result.event().to_string(), result.age().to_string());
+            *dropped.entry(key).or_insert(0) += 1;
+        }
+    }
+    
+    let mut unmatched: Vec<UnmatchedKey> = dropped.into_iter()
+        .map(|((sex, event, age), results)| UnmatchedKey { sex, event, age, results })
+        .collect();
+    unmatched.sort_by(|a, b| {
+        a.sex.cmp(&b.sex)
+            .then_with(|| a.event.cmp(&b.event))
+            .then_with(|| age_sort_key(&a.age).cmp(&age_sort_key(&b.age)))
+    });
+    
+    unmatched
+}
+
+// Results per sex field that no standards gender matches (a filename "B"
+// missing from sex_codes), in code order. These count nowhere.
+pub fn unmapped_sexes(
+    meet_results: &[MeetResult],
+    standards: &HashMap<String, EventStandards>,
+) -> BTreeMap<String, usize> {
+    let mut unmapped: BTreeMap<String, usize> = BTreeMap::new();
+    for result in meet_results.iter().filter(|r| !standards.contains_key(r.sex())) {
+        *unmapped.entry(result.sex().to_string()).or_insert(0) += 1;
+    }
+    unmapped
+}
+
+// Athletes whose qualifying status differs between last season's standards
+// and the current ones, once per (name, age group, event) by best time. Each
+// standards file matches age groups on its own; the current file's group is
+// reported. Relays are left out. Sorted by sex, age group, event, then time.
+pub fn status_changes(
+    meet_results: &[MeetResult],
+    previous_standards: &HashMap<String, EventStandards>,
+    standards: &HashMap<String, EventStandards>,
+    tolerance: f64,
+) -> Vec<StatusChange> {
+    let mut changes: HashMap<(String, String, String, String), StatusChange> = HashMap::new();
+    
+    for result in best_times(meet_results) {
+        if result.is_relay {
+            continue;
+        }
+        
+        let previous = matched_standard(&result, previous_standards);
+        let current = matched_standard(&result, standards);
+        let qualified_before = previous.as_ref()
+            .is_some_and(|(_, standard)| makes_cut(result.time, *standard, tolerance));
+        let qualified_now = current.as_ref()
+            .is_some_and(|(_, standard)| makes_cut(result.time, *standard, tolerance));
+        if qualified_before == qualified_now {
+            continue;
+        }
+        
+        let age_group = match current.as_ref().or(previous.as_ref()) {
+            Some((age_group, _)) => age_group.clone(),
+            None => continue,
+        };
+        
+        // Two entry ages can share an age group; keep the faster swim
+        let key = (result.name.clone(), result.sex().to_string(), age_group.clone(),
+                   result.event().to_string());
+        match changes.get(&key) {
+            Some(existing) if existing.time <= result.time => {}
+            _ => {
+                changes.insert(key, StatusChange {
+                    name: result.name.clone(),
+                    sex: result.sex().to_string(),
+                    age_group,
+                    event: result.event().to_string(),
+                    time: result.time,
+                    previous_standard: previous.map(|(_, standard)| standard),
+                    standard: current.map(|(_, standard)| standard),
+                    newly_qualified: qualified_now,
+                });
+            }
+        }
+    }
+    
+    let mut changes: Vec<StatusChange> = changes.into_values().collect();
+    changes.sort_by(|a, b| {
+        a.sex.cmp(&b.sex)
+            .then_with(|| age_sort_key(&a.age_group).cmp(&age_sort_key(&b.age_group)))
+            .then_with(|| a.event.cmp(&b.event))
+            .then_with(|| a.time.total_cmp(&b.time))
+    });
+    
+    changes
+}
+
+// Every swim that qualifies under the matched age group but has no standard
+// for its exact age (see AgeMismatch). Sorted by sex, age group, event, then time.
+pub fn age_mismatches(
+    meet_results: &[MeetResult],
+    standards: &HashMap<String, EventStandards>,
+    tolerance: f64,
+) -> Vec<AgeMismatch> {
+    let mut mismatches = Vec::new();
+    
+    for result in meet_results {
+        let event_standards = match standards.get(result.sex()).and_then(|g| g.get(result.event())) {
+            Some(e) => e,
+            None => continue,
+        };
+        if cuts_for_age(event_standards, &result.age).is_some() {
+            continue;
+        }
+        
+        if let Some((age_group, standard)) = matched_standard(result, standards) {
+            if makes_cut(result.time, standard, tolerance) {
+                mismatches.push(AgeMismatch {
+                    name: result.name.clone(),
+                    sex: result.sex().to_string(),
+                    age: result.age().to_string(),
+                    age_group,
+                    event: result.event().to_string(),
+                    time: result.time,
+                    standard,
+                });
+            }
+        }
+    }
+    
+    mismatches.sort_by(|a, b| {
+        a.sex.cmp(&b.sex)
+            .then_with(|| age_sort_key(&a.age_group).cmp(&age_sort_key(&b.age_group)))
+            .then_with(|| a.event.cmp(&b.event))
+            .then_with(|| a.time.total_cmp(&b.time))
+    });
+    
+    mismatches
+}
+
+// Column labels for age groups fed by multi-year meet files: the "14" group
+// shows as "13-14" when its swimmers came from 13-14 files. Groups fed by
+// conflicting ranges, single ages, or "00-12" style &under files keep their age.
+pub fn age_group_labels(
+    meet_results: &[MeetResult],
+    standards: &HashMap<String, EventStandards>,
+) -> HashMap<(String, String), String> {
+    let mut ranges: HashMap<(String, String), HashSet<String>> = HashMap::new();
+    
+    for result in meet_results {
+        if !spans_years(result) {
+            continue;
+        }
+        
+        if let Some(matched_age) = result_age_group(result, standards) {
+            ranges.entry((result.sex().to_string(), matched_age))
+                .or_default()
+                .insert(format!("{}-{}", result.age_min, result.age));
+        }
+    }
+    
+    ranges.into_iter()
+        .filter(|(_, labels)| labels.len() == 1)
+        .filter_map(|(key, labels)| labels.into_iter().next().map(|label| (key, label)))
+        .collect()
+}
+
+// Whether a result came from a multi-year file ("13-14"), not a single age or
+// an "00-12" style &under file
+fn spans_years(result: &MeetResult) -> bool {
+    match (parse_age(&result.age_min), parse_age(&result.age)) {
+        (Some(min), Some(max)) => min > 0 && min < max,
+        _ => false,
+    }
+}
+
+pub fn count_unique_qualifiers(
+    meet_results: &[MeetResult],
+    standards: &HashMap<String, EventStandards>,
+    tolerance: f64,
+) -> HashMap<(String, String), HashSet<String>> {
+    let mut unique_qualifiers: HashMap<(String, String), HashSet<String>> = HashMap::new();
+    
+    for result in meet_results {
+        // Relays are team entries, not individual athletes
+        if result.is_relay || !has_athlete_name(result) {
+            continue;
+        }
+        
+        if let Some((matched_age, qualifying_time)) = matched_standard(result, standards) {
+            if makes_cut(result.time, qualifying_time, tolerance) {
+                // Use the MATCHED age, not the original age
+                unique_qualifiers.entry((result.sex().to_string(), matched_age))
+                    .or_default()
+                    .insert(result.name.clone());
+            }
+        }
+    }
+    
+    unique_qualifiers
+}
+
+// Number of distinct events each athlete qualified in, keyed like
+// qualifying_events
+pub fn count_qualifying_events(
+    meet_results: &[MeetResult],
+    standards: &HashMap<String, EventStandards>,
+    tolerance: f64,
+) -> HashMap<(String, String, String), usize> {
+    qualifying_events(meet_results, standards, tolerance)
+        .into_iter()
+        .map(|(key, events)| (key, events.len()))
+        .collect()
+}
+
+// Distinct events each athlete qualified in, keyed by (name, sex, matched age
+// group). Same matching as count_unique_qualifiers, so every athlete it lists
+// is here; relays are left out.
+pub fn qualifying_events(
+    meet_results: &[MeetResult],
+    standards: &HashMap<String, EventStandards>,
+    tolerance: f64,
+) -> HashMap<(String, String, String), HashSet<String>> {
+    let mut events: HashMap<(String, String, String), HashSet<String>> = HashMap::new();
+    
+    for result in meet_results {
+        if result.is_relay || !has_athlete_name(result) {
+            continue;
+        }
+        
+        if let Some((matched_age, qualifying_time)) = matched_standard(result, standards) {
+            if makes_cut(result.time, qualifying_time, tolerance) {
+                events.entry((result.name.clone(), result.sex().to_string(), matched_age))
+                    .or_default()
+                    .insert(result.event().to_string());
+            }
+        }
+    }
+    
+    events
+}
+
+// Every athlete from qualifying_events with their events, by sex, age group
+// and name. Events follow the gender's order in `event_orders`; any it doesn't
+// list go last, alphabetically.
+pub fn athlete_roster(
+    events: HashMap<(String, String, String), HashSet<String>>,
+    event_orders: &HashMap<String, Vec<String>>,
+) -> Vec<RosterEntry> {
+    let mut roster: Vec<RosterEntry> = events.into_iter()
+        .map(|((name, sex, age_group), events)| {
+            let order = event_orders.get(&sex);
+            let position = |event: &String| {
+                order.and_then(|order| order.iter().position(|e| e == event)).unwrap_or(usize::MAX)
+            };
+            let mut events: Vec<String> = events.into_iter().collect();
+            events.sort_by(|a, b| position(a).cmp(&position(b)).then_with(|| a.cmp(b)));
+            RosterEntry { name, sex, age_group, events }
+        })
+        .collect();
+    roster.sort_by(|a, b| {
+        a.sex.cmp(&b.sex)
+            .then_with(|| age_sort_key(&a.age_group).cmp(&age_sort_key(&b.age_group)))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    roster
+}
+
+// Athletes from count_qualifying_events with two or more events, most events
+// first, then by sex, age group and name
+pub fn multi_event_qualifiers(counts: &HashMap<(String, String, String), usize>) -> Vec<MultiEventQualifier> {
+    let mut rows: Vec<MultiEventQualifier> = counts.iter()
+        .filter(|(_, &events)| events > 1)
+        .map(|((name, sex, age_group), &events)| MultiEventQualifier {
+            name: name.clone(),
+            sex: sex.clone(),
+            age_group: age_group.clone(),
+            events,
+        })
+        .collect();
+    rows.sort_by(|a, b| {
+        b.events.cmp(&a.events)
+            .then_with(|| a.sex.cmp(&b.sex))
+            .then_with(|| age_sort_key(&a.age_group).cmp(&age_sort_key(&b.age_group)))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    rows
+}
+
+// Count total unique athletes per gender/age (using matched ages)
+pub fn count_total_athletes(
+    meet_results: &[MeetResult],
+    standards: &HashMap<String, EventStandards>,
+) -> HashMap<(String, String), HashSet<String>> {
+    let mut total_athletes: HashMap<(String, String), HashSet<String>> = HashMap::new();
+    
+    for result in meet_results {
+        if !result.is_relay && has_athlete_name(result) {
+            // Same age matching as the qualifier counts, so totals line up
+            if let Some(matched_age) = result_age_group(result, standards) {
+                total_athletes.entry((result.sex().to_string(), matched_age))
+                    .or_default()
+                    .insert(result.name.clone());
+            }
+        }
+    }
+    
+    total_athletes
+}
+
+// Distinct athletes and swims per (sex, entry age, event) from the meet results
+// alone, so every event swum is listed whether or not it has a standard. Rows
+// go by sex, age and then event (stroke, then distance). Relays and results
+// without a name are left out, as in count_total_athletes.
+pub fn participation(meet_results: &[MeetResult]) -> Vec<Participation> {
+    let mut swims: HashMap<(String, String, String), (HashSet<&str>, usize)> = HashMap::new();
+    
+    for result in meet_results {
+        if result.is_relay || !has_athlete_name(result) {
+            continue;
+        }
+        
+        let age = if spans_years(result) {
+            format!("{}-{}", result.age_min, result.age)
+        } else {
+            result.age().to_string()
+        };
+        let entry = swims.entry((result.sex().to_string(), age, result.event().to_string()))
+            .or_default();
+        entry.0.insert(&result.name);
+        entry.1 += 1;
+    }
+    
+    let mut events: Vec<String> = swims.keys().map(|(_, _, event)| event.clone()).collect();
+    events.sort();
+    events.dedup();
+    sort_events(&mut events, EventSort::StrokeDistance);
+    let position = |event: &String| events.iter().position(|e| e == event);
+    
+    let mut rows: Vec<Participation> = swims.into_iter()
+        .map(|((sex, age, event), (athletes, swims))| Participation {
+            sex,
+            age,
+            event,
+            athletes: athletes.len(),
+            swims,
+        })
+        .collect();
+    rows.sort_by(|a, b| {
+        a.sex.cmp(&b.sex)
+            .then_with(|| age_sort_key(&a.age).cmp(&age_sort_key(&b.age)))
+            .then_with(|| a.age.cmp(&b.age))
+            .then_with(|| position(&a.event).cmp(&position(&b.event)))
+    });
+    rows
+}
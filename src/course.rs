@@ -0,0 +1,148 @@
+use regex::Regex;
+use serde::Deserialize;
+use std::sync::LazyLock;
+
+// Pool course a time was swum in. Standards are published for one course, so
+// results from the other two are converted before comparing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Course {
+    #[default]
+    Scy,
+    Scm,
+    Lcm,
+}
+
+impl Course {
+    // Course code as written in meet filenames ("SCY", "scm", ...)
+    pub fn from_code(code: &str) -> Option<Course> {
+        match code.trim().to_ascii_uppercase().as_str() {
+            "SCY" => Some(Course::Scy),
+            "SCM" => Some(Course::Scm),
+            "LCM" => Some(Course::Lcm),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Course::Scy => "SCY",
+            Course::Scm => "SCM",
+            Course::Lcm => "LCM",
+        }
+    }
+}
+
+// USA Swimming factor for 50-400 yard events going to meters
+const SCY_TO_METERS: f64 = 1.11;
+
+// Freestyle distance events: yards, meters, and the yards -> LCM factor
+const DISTANCE_EVENTS: [(u32, u32, f64); 3] = [
+    (500, 400, 0.8925),
+    (1000, 800, 0.8925),
+    (1650, 1500, 1.02),
+];
+
+// Normalized events: "100Bk", "4x50FrR"
+static EVENT_PARTS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:(\d+)x)?(\d+)([A-Za-z]{2})(R?)$").unwrap()
+});
+
+struct EventParts {
+    distance: u32, // Total distance, all legs for relays
+    stroke: String,
+    is_relay: bool,
+}
+
+fn event_parts(event: &str) -> Option<EventParts> {
+    let caps = EVENT_PARTS.captures(event)?;
+    let legs: u32 = caps.get(1).map_or(Ok(1), |m| m.as_str().parse()).ok()?;
+    let leg_distance: u32 = caps[2].parse().ok()?;
+    Some(EventParts {
+        distance: legs * leg_distance,
+        stroke: caps[3].to_string(),
+        is_relay: !caps[4].is_empty(),
+    })
+}
+
+// Seconds added per 50 going from a short course to long course pool (fewer
+// walls to push off)
+fn turn_increment(stroke: &str) -> f64 {
+    match stroke {
+        "Bk" => 0.6,
+        "Br" => 1.0,
+        "Bu" => 0.7,
+        _ => 0.8, // Fr, Me
+    }
+}
+
+// Yard freestyle distance event for a (yards, meters) pair, if any
+fn distance_event(parts: &EventParts, course: Course) -> Option<(u32, u32, f64)> {
+    if parts.is_relay || parts.stroke != "Fr" {
+        return None;
+    }
+    DISTANCE_EVENTS.iter().copied().find(|(yards, meters, _)| match course {
+        Course::Scy => *yards == parts.distance,
+        Course::Scm | Course::Lcm => *meters == parts.distance,
+    })
+}
+
+// Event name in the target course: yard distance freestyle events are swum as
+// shorter meter events (500 <-> 400, 1000 <-> 800, 1650 <-> 1500)
+pub fn convert_event(event: &str, from_course: Course, to_course: Course) -> String {
+    let from_yards = from_course == Course::Scy;
+    let to_yards = to_course == Course::Scy;
+    if from_yards == to_yards {
+        return event.to_string();
+    }
+
+    match event_parts(event).and_then(|parts| distance_event(&parts, from_course)) {
+        Some((yards, meters, _)) => format!("{}Fr", if to_yards { yards } else { meters }),
+        None => event.to_string(),
+    }
+}
+
+// Converts a time in seconds between courses using the USA Swimming factors.
+// `event` is the normalized event name in `from_course`. SCY <-> LCM uses the
+// 1.11 factor plus a per-stroke increment per 50 (flat factors for the
+// freestyle distance events); SCM <-> LCM differs by the increment alone, so
+// everything goes through LCM.
+pub fn convert_time(time: f64, from_course: Course, to_course: Course, event: &str) -> f64 {
+    if from_course == to_course {
+        return time;
+    }
+
+    let lcm_event = convert_event(event, from_course, Course::Lcm);
+    let parts = match event_parts(&lcm_event) {
+        Some(p) => p,
+        // Unknown event: fall back to the plain yards/meters factor
+        None => {
+            return match (from_course == Course::Scy, to_course == Course::Scy) {
+                (true, false) => time * SCY_TO_METERS,
+                (false, true) => time / SCY_TO_METERS,
+                _ => time,
+            };
+        }
+    };
+
+    let increment = turn_increment(&parts.stroke) * parts.distance as f64 / 50.0;
+    let distance = distance_event(&parts, Course::Lcm);
+
+    let lcm_time = match from_course {
+        Course::Lcm => time,
+        Course::Scm => time + increment,
+        Course::Scy => match distance {
+            Some((_, _, factor)) => time * factor,
+            None => time * SCY_TO_METERS + increment,
+        },
+    };
+
+    match to_course {
+        Course::Lcm => lcm_time,
+        Course::Scm => lcm_time - increment,
+        Course::Scy => match distance {
+            Some((_, _, factor)) => lcm_time / factor,
+            None => (lcm_time - increment) / SCY_TO_METERS,
+        },
+    }
+}
@@ -0,0 +1,117 @@
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum AnalysisError {
+    #[error("Time standards file not found: {}", .0.display())]
+    StandardsNotFound(PathBuf),
+
+    #[error("Data folder not found: {}", .0.display())]
+    DataFolderNotFound(PathBuf),
+
+    #[error("No meet files in {} starting with {}",
+            .data.iter().map(|d| d.display().to_string()).collect::<Vec<_>>().join(", "),
+            .prefixes.join(", "))]
+    NoMeetFiles { data: Vec<PathBuf>, prefixes: Vec<String> },
+
+    #[error("Time standards workbook {} failed validation:\n  - {}", .path.display(), .problems.join("\n  - "))]
+    InvalidStandards { path: PathBuf, problems: Vec<String> },
+
+    #[error("Invalid config file {}: {reason}", .path.display())]
+    Config { path: PathBuf, reason: String },
+
+    #[error("Cannot parse filename {filename}: {reason}")]
+    FilenameParse { filename: String, reason: String },
+
+    #[error("Invalid age range format: {0} (expected XX-YY or a single age)")]
+    InvalidAgeRange(String),
+
+    #[error("Cannot open workbook {}: {source}", .path.display())]
+    Workbook {
+        path: PathBuf,
+        #[source]
+        source: calamine::Error,
+    },
+
+    #[error("{} is password-protected; save an unprotected copy to include it", .path.display())]
+    ProtectedWorkbook { path: PathBuf },
+
+    #[error("{} is corrupt or incomplete (try re-exporting it): {source}", .path.display())]
+    CorruptWorkbook {
+        path: PathBuf,
+        #[source]
+        source: calamine::Error,
+    },
+
+    #[error("{} has a .{extension} extension but is a .{actual} workbook; rename it to .{actual}", .path.display())]
+    WorkbookFormat { path: PathBuf, extension: String, actual: String },
+
+    #[error("Cannot read CSV file {}: {source}", .path.display())]
+    Csv {
+        path: PathBuf,
+        #[source]
+        source: csv::Error,
+    },
+
+    #[error("Cannot write CSV file {}: {source}", .path.display())]
+    CsvOutput {
+        path: PathBuf,
+        #[source]
+        source: csv::Error,
+    },
+
+    #[error("Cannot read sheet '{sheet}': {source}")]
+    SheetRead {
+        sheet: String,
+        #[source]
+        source: calamine::Error,
+    },
+
+    #[error("{count} results are older than every age group in the standards (first: {example}); \
+             use --over-age clamp or drop to accept them")]
+    OverAge { count: usize, example: String },
+
+    #[error("--strict: {count} warnings recorded ({summary}); details in {}", .path.display())]
+    StrictWarnings { count: usize, summary: String, path: PathBuf },
+
+    #[error("Cannot write output: {0}")]
+    Output(#[from] rust_xlsxwriter::XlsxError),
+
+    #[error("Output folder {} could not be created: {source}", .path.display())]
+    OutputFolder {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Output folder not found: {}", .0.display())]
+    OutputFolderNotFound(PathBuf),
+
+    #[error("Cannot replace {} (is it open in another program?); the new output is in {}: {source}",
+            .path.display(), .temp.display())]
+    OutputReplace {
+        path: PathBuf,
+        temp: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Cannot write JSON output: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+impl AnalysisError {
+    // Why a meet file was left out, for the skipped files summary: "protected",
+    // "corrupt", "wrong format", or "unreadable" for anything else
+    pub fn skip_reason(&self) -> &'static str {
+        match self {
+            AnalysisError::ProtectedWorkbook { .. } => "protected",
+            AnalysisError::CorruptWorkbook { .. } => "corrupt",
+            AnalysisError::WorkbookFormat { .. } => "wrong format",
+            _ => "unreadable",
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use calamine::{open_workbook_auto, Data, Reader};
+use log::{debug, info};
+use std::path::Path;
+
+use crate::error::AnalysisError;
+use crate::report::QualifierReport;
+
+// Sheet --append keeps between runs. ("History" itself is reserved by Excel.)
+pub const HISTORY_SHEET: &str = "Count History";
+
+// Columns before the dated count columns; together they are a row's key
+pub const HISTORY_KEY_COLUMNS: [&str; 4] = ["Gender", "Course", "Age Group", "Event"];
+
+// Qualifying swims per gender, course, age group and event, one column per run
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CountHistory {
+    pub columns: Vec<String>,
+    pub rows: Vec<HistoryRow>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryRow {
+    pub gender: String,
+    pub course: String, // Empty unless the counts were split per course
+    pub age_group: String,
+    pub event: String,
+    // Parallel to CountHistory::columns; None where the row had no count yet
+    pub values: Vec<Option<f64>>,
+}
+
+impl HistoryRow {
+    fn key(&self) -> (&str, &str, &str, &str) {
+        (&self.gender, &self.course, &self.age_group, &self.event)
+    }
+}
+
+fn cell_text(cell: Option<&Data>) -> String {
+    match cell {
+        Some(Data::Empty) | None => String::new(),
+        Some(data) => data.to_string().trim().to_string(),
+    }
+}
+
+fn cell_number(cell: Option<&Data>) -> Option<f64> {
+    match cell {
+        Some(Data::Float(n)) => Some(*n),
+        Some(Data::Int(n)) => Some(*n as f64),
+        Some(Data::String(s)) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+// The Count History sheet of an earlier output. Empty when the file doesn't
+// exist yet or has no such sheet (e.g. it was written without --append).
+pub fn read_count_history(path: &Path) -> Result<CountHistory, AnalysisError> {
+    if !path.exists() {
+        info!("{} doesn't exist yet; starting a new count history", path.display());
+        return Ok(CountHistory::default());
+    }
+
+    let mut workbook = open_workbook_auto(path)
+        .map_err(|source| AnalysisError::Workbook { path: path.to_path_buf(), source })?;
+    if !workbook.sheet_names().iter().any(|name| name == HISTORY_SHEET) {
+        info!("{} has no '{}' sheet; starting a new count history", path.display(), HISTORY_SHEET);
+        return Ok(CountHistory::default());
+    }
+    let range = workbook.worksheet_range(HISTORY_SHEET)
+        .map_err(|source| AnalysisError::Workbook { path: path.to_path_buf(), source })?;
+
+    let mut rows = range.rows();
+    let columns: Vec<String> = match rows.next() {
+        Some(header) => header.iter().skip(HISTORY_KEY_COLUMNS.len()).map(|c| cell_text(Some(c))).collect(),
+        None => return Ok(CountHistory::default()),
+    };
+
+    let mut history = CountHistory { columns, rows: Vec::new() };
+    for row in rows {
+        let event = cell_text(row.get(3));
+        if event.is_empty() {
+            continue;
+        }
+        history.rows.push(HistoryRow {
+            gender: cell_text(row.first()),
+            course: cell_text(row.get(1)),
+            age_group: cell_text(row.get(2)),
+            event,
+            values: (0..history.columns.len())
+                .map(|i| cell_number(row.get(HISTORY_KEY_COLUMNS.len() + i)))
+                .collect(),
+        });
+    }
+
+    debug!("Read {} count history rows over {} columns", history.rows.len(), history.columns.len());
+    Ok(history)
+}
+
+// Adds `column` with the report's qualifying swims (individual events and
+// relays). Rows are matched on gender, course, age group label and event;
+// keys not seen before are added at the end, blank in the older columns. A
+// column that already exists (a second run on the same date) is overwritten.
+pub fn append_counts(history: &mut CountHistory, column: &str, report: &QualifierReport) {
+    let col = match history.columns.iter().position(|c| c == column) {
+        Some(col) => {
+            debug!("Count history already has a '{}' column; replacing it", column);
+            for row in &mut history.rows {
+                row.values[col] = None;
+            }
+            col
+        }
+        None => {
+            history.columns.push(column.to_string());
+            for row in &mut history.rows {
+                row.values.push(None);
+            }
+            history.columns.len() - 1
+        }
+    };
+
+    for gender_report in &report.genders {
+        let course = gender_report.course.clone().unwrap_or_default();
+        // Standard-less events have nothing to track
+        for event in gender_report.events.iter().chain(&gender_report.relays).filter(|e| !e.no_standard) {
+            for (age, count) in event.counts.iter().enumerate() {
+                let age_group = &gender_report.age_labels[age];
+                let key = (gender_report.gender.as_str(), course.as_str(), age_group.as_str(), event.event.as_str());
+                let value = Some(count.qualifying_swims as f64);
+                match history.rows.iter_mut().find(|row| row.key() == key) {
+                    Some(row) => row.values[col] = value,
+                    None => {
+                        let mut values = vec![None; history.columns.len()];
+                        values[col] = value;
+                        history.rows.push(HistoryRow {
+                            gender: gender_report.gender.clone(),
+                            course: course.clone(),
+                            age_group: age_group.clone(),
+                            event: event.event.clone(),
+                            values,
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,74 @@
+//! Counts swimmers meeting time standards by age group and event.
+//!
+//! The `standards` binary is a thin CLI over this crate; the parsing and
+//! counting functions can also be used directly from tests or other tools.
+
+mod age;
+mod cache;
+mod config;
+mod count;
+mod course;
+mod error;
+mod history;
+mod manifest;
+mod meet;
+mod normalize;
+mod output;
+mod report;
+mod standards;
+mod time;
+mod warning;
+
+pub use age::{
+    age_group_for, age_in_range, age_sort_key, apply_over_age_policy, find_best_age_match,
+    find_best_age_range_match, is_open_age, is_over_age, match_age_group, normalize_age, parse_age,
+    parse_age_range, unparseable_ages,
+};
+pub use cache::{load_time_standards_cached, standards_cache_path};
+pub use config::{
+    column_letter, parse_column, Config, CountMode, EventSort, Layout, OutputFormat, OverAgePolicy,
+    RoundPolicy, DATA_FOLDER, NAME_COLUMN, NEAR_MISS_PCT, OUTPUT_FILE, TEAM_PREFIX, TIME_COLUMN,
+    TIME_STANDARDS_FILE,
+};
+pub use count::{
+    age_group_labels, age_mismatches, athlete_roster, best_times, best_vs_cut,
+    count_best_time_cut_levels, count_best_time_qualifiers, count_cut_levels, count_near_qualifiers,
+    count_qualifiers, count_qualifying_events, count_total_athletes, count_unique_qualifiers,
+    multi_event_qualifiers, participation, qualifier_details, qualifying_events, rankings,
+    status_changes, to_standards_course, top_n_per_event, unmapped_sexes, unmatched_results,
+    AgeMismatch, BestVsCut, MultiEventQualifier, Participation, Ranking, RosterEntry,
+    StandardComparison, StatusChange, UnmatchedKey,
+};
+pub use course::{convert_event, convert_time, Course};
+pub use error::AnalysisError;
+pub use history::{
+    append_counts, read_count_history, CountHistory, HistoryRow, HISTORY_KEY_COLUMNS, HISTORY_SHEET,
+};
+pub use manifest::{manifest_path, write_manifest, InputFile, Manifest, ManifestTotals, VERSION};
+pub use meet::{meet_date, parse_meet_csv, parse_meet_file, results_by_course, MeetResult, Round};
+pub use normalize::{
+    default_stroke_names, event_parts, is_event_name, normalize_any_event, normalize_any_event_with,
+    normalize_event_name, normalize_event_name_with, normalize_name, normalize_relay_event,
+    normalize_relay_event_with, parse_event_components, sort_events, split_event, stroke_rank,
+    EventComponents, EventNormalizer, EventParts, RELAY_LEGS, SPLIT_DISTANCE,
+};
+pub use output::{temp_path, write_atomic};
+pub use report::{
+    build_report, combine_ages, gender_label, long_rows, markdown_report, sanitize_sheet_name,
+    stroke_totals, with_meet_events, write_json, write_markdown, write_roster_csv, write_workbook,
+    AgeGroupCount, AgeGroupSummary, CutLevelCounts, EventCounts, GenderReport, LongRow,
+    QualifierReport, ALL_AGES,
+};
+pub use standards::{
+    cut_level_order, highest_cut_level, load_time_standards, load_time_standards_labeled,
+    load_time_standards_with, makes_cut, qualifying_time, standards_event_names, AgeGroupStandards,
+    AgeLabels, CutLevelKey, CutTimes, EventOrders, EventStandards, StandardKey, StandardsColumns,
+    StandardsEventName, DEFAULT_CUT_LEVEL,
+};
+pub use time::{
+    format_time, is_no_result, time_to_seconds, TimeCell, CLOCK_TIME_SECONDS, NO_RESULT_SENTINELS,
+    STATUS_CODES,
+};
+pub use warning::{
+    record_warning, summarize_warnings, warnings_path, write_warnings, Warning, WarningCategory,
+};
@@ -1,420 +1,453 @@
-use calamine::{open_workbook, Reader, Xlsx, Data};
-use rust_xlsxwriter::Workbook;
-use std::collections::{HashMap, HashSet};
-use std::path::Path;
-
-// *** CONFIGURATION ***
-const TIME_STANDARDS_FILE: &str = "timestandards.xlsx";
-const DATA_FOLDER: &str = "data";
-const OUTPUT_FILE: &str = "qualifier_counts.xlsx";
-
-#[derive(Debug, Clone)]
-struct MeetResult {
-    course: String,
-    sex: String,
-    age: String,
-    event: String,
-    time: f64,
-    name: String,
-}
+use chrono::NaiveDate;
+use clap::Parser;
+use indicatif::{ProgressBar, ProgressStyle};
+use log::{debug, info, warn, Level, LevelFilter};
+use standards::{
+    age_group_labels, age_in_range, age_mismatches, age_sort_key, append_counts,
+    apply_over_age_policy, athlete_roster, best_vs_cut, build_report, combine_ages,
+    count_best_time_cut_levels, count_best_time_qualifiers, count_cut_levels, count_near_qualifiers,
+    count_qualifiers, count_qualifying_events, count_total_athletes, count_unique_qualifiers,
+    gender_label, load_time_standards_cached, manifest_path, meet_date, multi_event_qualifiers,
+    parse_age, parse_column, parse_meet_csv, parse_meet_file, participation, qualifier_details,
+    qualifying_events, rankings, read_count_history, record_warning, results_by_course, sort_events,
+    standards_event_names, status_changes, summarize_warnings, to_standards_course, unmapped_sexes,
+    unmatched_results, unparseable_ages, warnings_path, with_meet_events, write_json,
+    write_manifest, write_markdown, write_roster_csv, write_warnings, write_workbook, AnalysisError,
+    Config, CountMode, Course, EventSort, EventStandards, InputFile, Layout, Manifest,
+    ManifestTotals, MeetResult, OutputFormat, OverAgePolicy, QualifierReport, RoundPolicy,
+    StandardsEventName, Warning, WarningCategory, OUTPUT_FILE, VERSION,
+};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 
-type StandardKey = (String, String, String); // (sex, age, event)
-type AgeGroupStandards = HashMap<String, f64>; // {age_group: qualifying_time}
-type EventStandards = HashMap<String, AgeGroupStandards>; // {event: {age: time}}
+// Exit status of a run that wrote its output but found no qualifiers.
+// Errors (including --strict) exit with 1.
+const NO_QUALIFIERS_EXIT: u8 = 2;
 
-fn normalize_event_name(event: &str) -> Option<String> {
-    if event.trim().is_empty() {
-        return None;
-    }
+/// Count swimmers meeting time standards by age group and event
+#[derive(Parser, Debug)]
+#[command(version, about)]
+struct Args {
+    /// TOML file with run settings; flags override its values
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
 
-    let mut normalized = event.trim().to_string();
-    
-    // Remove 'm' and ALL spaces
-    normalized = normalized.replace('m', "").replace(' ', "");
-    
-    // Normalize stroke names to abbreviations
-    // Full names from data files -> 2-letter abbreviations
-    normalized = normalized.replace("Free", "Fr");
-    normalized = normalized.replace("Fly", "Bu");  // Butterfly
-    normalized = normalized.replace("Back", "Bk");
-    normalized = normalized.replace("Breast", "Br");
-    normalized = normalized.replace("M.E.", "Me");  // Medley (I.M.)
-    normalized = normalized.replace("M.E", "Me");
-    normalized = normalized.replace("I.M.", "Me");
-    normalized = normalized.replace("I.M", "Me");
-    
-    // Also handle abbreviated forms that might come from standards file
-    normalized = normalized.replace("FL", "Bu");
-    
-    Some(normalized)
+    /// Time standards workbook (one tab per gender) [default: timestandards.xlsx]
+    #[arg(long, value_name = "FILE")]
+    standards: Option<PathBuf>,
+
+    /// Last season's standards workbook; adds a "Changed Status" sheet comparing the two
+    #[arg(long, value_name = "FILE")]
+    previous_standards: Option<PathBuf>,
+
+    /// Folder containing the meet result files; repeat or comma-separate for several [default: data]
+    #[arg(long, value_name = "DIR", value_delimiter = ',')]
+    data: Vec<PathBuf>,
+
+    /// Output workbook for the qualifier counts [default: qualifier_counts.xlsx]
+    #[arg(long, value_name = "FILE")]
+    output: Option<PathBuf>,
+
+    /// Meet filename prefix; repeat to include several clubs [default: CAN-MBSK_]
+    #[arg(long = "prefix", value_name = "PREFIX")]
+    prefixes: Vec<String>,
+
+    /// Time column, 0-based or a letter like J; overrides header detection
+    #[arg(long, value_name = "COLUMN", value_parser = column_arg)]
+    time_col: Option<usize>,
+
+    /// Name column, 0-based or a letter like E; overrides header detection
+    #[arg(long, value_name = "COLUMN", value_parser = column_arg)]
+    name_col: Option<usize>,
+
+    /// Column with the first 50 split, 0-based or a letter; splits are checked against the 50 cuts
+    #[arg(long, value_name = "COLUMN", value_parser = column_arg)]
+    split_col: Option<usize>,
+
+    /// Prelim time column, 0-based or a letter; the time column is then read as the final
+    #[arg(long, value_name = "COLUMN", value_parser = column_arg)]
+    prelim_col: Option<usize>,
+
+    /// Which time counts when a swimmer has both a prelim and a final [default: best]
+    #[arg(long, value_enum)]
+    round_policy: Option<RoundPolicy>,
+
+    /// Standards tab column with the event names, 0-based or a letter [default: A]
+    #[arg(long, value_name = "COLUMN", value_parser = column_arg)]
+    standards_event_col: Option<usize>,
+
+    /// Standards tab column of the first age group header [default: the column after the events]
+    #[arg(long, value_name = "COLUMN", value_parser = column_arg)]
+    standards_age_col: Option<usize>,
+
+    /// Only analyze this normalized event (e.g. 50Bu, 200Me); repeat for several
+    #[arg(long = "filter-event", value_name = "EVENT")]
+    filter_events: Vec<String>,
+
+    /// Leave out swimmers and age groups younger than this
+    #[arg(long, value_name = "AGE")]
+    min_age: Option<i32>,
+
+    /// Leave out swimmers and age groups older than this
+    #[arg(long, value_name = "AGE")]
+    max_age: Option<i32>,
+
+    /// Only use meets on or after this date (YYYY-MM-DD, from the filename)
+    #[arg(long, value_name = "DATE")]
+    since: Option<NaiveDate>,
+
+    /// Only use meets on or before this date (YYYY-MM-DD, from the filename)
+    #[arg(long, value_name = "DATE")]
+    until: Option<NaiveDate>,
+
+    /// Output format; json and markdown write next to --output with a .json/.md extension [default: xlsx]
+    #[arg(long, value_enum)]
+    format: Option<OutputFormat>,
+
+    /// Count sheets as grids (wide) or one row per count for pivoting (long) [default: wide]
+    #[arg(long, value_enum)]
+    layout: Option<Layout>,
+
+    /// What the main count grid shows: every qualifying swim, or each athlete once per event [default: swims]
+    #[arg(long, value_enum)]
+    count_mode: Option<CountMode>,
+
+    /// Order of the event rows in the output [default: standards]
+    #[arg(long, value_enum)]
+    event_sort: Option<EventSort>,
+
+    /// Results older than every age group: count against the oldest, drop, or fail [default: clamp]
+    #[arg(long, value_enum)]
+    over_age: Option<OverAgePolicy>,
+
+    /// Report athletes within this percent of the cut as near misses [default: 2.0]
+    #[arg(long, value_name = "PCT")]
+    near_miss_pct: Option<f64>,
+
+    /// Seconds a swim may be over a cut and still qualify, e.g. 0.01 for touchpad timing [default: 0.0]
+    #[arg(long, value_name = "SECONDS")]
+    tolerance: Option<f64>,
+
+    /// Fastest athletes listed per sex/age/event on the Rankings sheet [default: 3]
+    #[arg(long, value_name = "N")]
+    top_n: Option<usize>,
+
+    /// Course the standards are published for; other courses are converted [default: scy]
+    #[arg(long, value_enum)]
+    standards_course: Option<Course>,
+
+    /// Re-parse the standards workbook instead of using its cache file
+    #[arg(long)]
+    no_cache: bool,
+
+    /// Write one output file per meet (<output>_<meet>.xlsx) instead of pooling all meets
+    #[arg(long)]
+    per_meet: bool,
+
+    /// Add a "Best Times vs Cut" sheet with the fastest swim per age group and event
+    #[arg(long)]
+    best_vs_cut: bool,
+
+    /// Drop duplicate (name, event, time) rows within a meet sheet
+    #[arg(long)]
+    dedup: bool,
+
+    /// Exit with an error when any warning was recorded (the output is still written)
+    #[arg(long)]
+    strict: bool,
+
+    /// Folder to write the output into, created if missing; --output's file name is kept
+    #[arg(long, value_name = "DIR")]
+    output_dir: Option<PathBuf>,
+
+    /// Also write the qualified athletes to this CSV: name, sex, age group and their events
+    #[arg(long, value_name = "FILE")]
+    roster_csv: Option<PathBuf>,
+
+    /// Write one file per gender (mens.xlsx, womens.xlsx) instead of one workbook with a sheet per gender
+    #[arg(long)]
+    split_by_gender: bool,
+
+    /// Add an "All Ages" column summing each event over the age groups
+    #[arg(long)]
+    combine_ages: bool,
+
+    /// Show only the "All Ages" column instead of one per age group
+    #[arg(long)]
+    combine_ages_only: bool,
+
+    /// Also list events swum at the meets that have no standard, marked "no standard" in the grids
+    #[arg(long)]
+    events_from_meets: bool,
+
+    /// Keep the existing output's "Count History" sheet and add this run's counts as a new dated column
+    #[arg(long)]
+    append: bool,
+
+    /// Print the normalized events read from the standards workbook, with their raw names, and exit
+    #[arg(long)]
+    list_events: bool,
+
+    /// Only parse the first N meet files by filename, for quick runs while tuning settings
+    #[arg(long, value_name = "N")]
+    sample: Option<usize>,
+
+    /// Don't shade the count grids with a color scale
+    #[arg(long)]
+    no_format: bool,
+
+    /// Show full debug traces (header dumps, per-file and per-event details)
+    #[arg(short, long, conflicts_with = "quiet")]
+    verbose: bool,
+
+    /// Only show warnings and errors
+    #[arg(short, long)]
+    quiet: bool,
 }
 
-fn normalize_age(age: &str) -> String {
-    // Remove "&U" suffix if present
-    age.trim().replace("&U", "")
+fn column_arg(value: &str) -> Result<usize, String> {
+    parse_column(value).ok_or_else(|| format!("'{}' is not a column index or letter (e.g. 9 or J)", value))
 }
 
-fn find_best_age_match(athlete_age: &str, available_ages: &[String]) -> Option<String> {
-    let athlete_age_num = athlete_age.parse::<i32>().ok()?;
-    
-    // Convert available ages to numbers
-    let mut age_nums: Vec<(i32, String)> = available_ages
-        .iter()
-        .filter_map(|a| {
-            a.parse::<i32>().ok().map(|num| (num, a.clone()))
-        })
-        .collect();
-    
-    if age_nums.is_empty() {
-        return None;
+// Command-line flags take precedence over the config file
+fn apply_args(config: &mut Config, args: &Args) {
+    if let Some(standards) = &args.standards {
+        config.standards = standards.clone();
     }
-    
-    // Sort by age
-    age_nums.sort_by_key(|(num, _)| *num);
-    
-    // Find exact match first
-    if let Some((_, age_str)) = age_nums.iter().find(|(num, _)| *num == athlete_age_num) {
-        return Some(age_str.clone());
+    if args.previous_standards.is_some() {
+        config.previous_standards = args.previous_standards.clone();
     }
-    
-    // If athlete is younger than minimum standard, use the minimum
-    if athlete_age_num < age_nums[0].0 {
-        return Some(age_nums[0].1.clone());
+    if !args.data.is_empty() {
+        config.data = args.data.clone();
     }
-    
-    // If athlete is older than maximum standard, use the maximum
-    if athlete_age_num > age_nums.last().unwrap().0 {
-        return Some(age_nums.last().unwrap().1.clone());
+    if let Some(output) = &args.output {
+        config.output = output.clone();
     }
-    
-    // Find closest age (shouldn't normally reach here, but just in case)
-    let closest = age_nums
-        .iter()
-        .min_by_key(|(num, _)| (athlete_age_num - num).abs())
-        .map(|(_, age_str)| age_str.clone());
-    
-    closest
-}
-
-fn time_to_seconds(value: &Data) -> Option<f64> {
-    match value {
-        Data::Float(f) => Some(*f),
-        Data::Int(i) => Some(*i as f64),
-        Data::DateTime(dt) => {
-            // Excel stores time as fraction of a day
-            // Convert to seconds: fraction_of_day * 24 hours * 60 minutes * 60 seconds
-            let seconds = dt.as_f64() * 86400.0; // 24 * 60 * 60 = 86400 seconds per day
-            Some(seconds)
-        }
-        Data::String(s) => {
-            let s = s.trim();
-            if s.is_empty() || s.eq_ignore_ascii_case("nan") {
-                return None;
-            }
-            
-            if s.contains(':') {
-                let parts: Vec<&str> = s.split(':').collect();
-                if parts.len() == 2 {
-                    let minutes = parts[0].parse::<f64>().ok()?;
-                    let seconds = parts[1].parse::<f64>().ok()?;
-                    return Some(minutes * 60.0 + seconds);
-                }
-            }
-            
-            s.parse::<f64>().ok()
-        }
-        _ => None,
+    if !args.prefixes.is_empty() {
+        config.team_prefixes = args.prefixes.clone();
+    }
+    if args.time_col.is_some() {
+        config.time_col = args.time_col;
+    }
+    if args.name_col.is_some() {
+        config.name_col = args.name_col;
+    }
+    if args.split_col.is_some() {
+        config.split_col = args.split_col;
+    }
+    if args.prelim_col.is_some() {
+        config.prelim_col = args.prelim_col;
+    }
+    if let Some(round_policy) = args.round_policy {
+        config.round_policy = round_policy;
+    }
+    if args.standards_event_col.is_some() {
+        config.standards_event_col = args.standards_event_col;
+    }
+    if args.standards_age_col.is_some() {
+        config.standards_age_col = args.standards_age_col;
+    }
+    if !args.filter_events.is_empty() {
+        config.events = args.filter_events.clone();
+    }
+    if args.min_age.is_some() {
+        config.min_age = args.min_age;
+    }
+    if args.max_age.is_some() {
+        config.max_age = args.max_age;
+    }
+    if args.since.is_some() {
+        config.since = args.since;
+    }
+    if args.until.is_some() {
+        config.until = args.until;
+    }
+    if let Some(format) = args.format {
+        config.format = format;
+    }
+    if let Some(layout) = args.layout {
+        config.layout = layout;
+    }
+    if let Some(count_mode) = args.count_mode {
+        config.count_mode = count_mode;
+    }
+    if let Some(event_sort) = args.event_sort {
+        config.event_sort = event_sort;
+    }
+    if let Some(over_age) = args.over_age {
+        config.over_age = over_age;
+    }
+    if let Some(pct) = args.near_miss_pct {
+        config.near_miss_pct = pct;
+    }
+    if let Some(tolerance) = args.tolerance {
+        config.tolerance = tolerance;
+    }
+    if let Some(top_n) = args.top_n {
+        config.top_n = top_n;
+    }
+    if let Some(course) = args.standards_course {
+        config.standards_course = course;
+    }
+    if args.no_format {
+        config.highlight = false;
+    }
+    if args.no_cache {
+        config.cache_standards = false;
+    }
+    if args.dedup {
+        config.dedup = true;
+    }
+    if args.per_meet {
+        config.per_meet = true;
+    }
+    if args.best_vs_cut {
+        config.best_vs_cut = true;
+    }
+    if args.strict {
+        config.strict = true;
+    }
+    if args.append {
+        config.append = true;
+    }
+    if args.output_dir.is_some() {
+        config.output_dir = args.output_dir.clone();
+    }
+    if args.roster_csv.is_some() {
+        config.roster_csv = args.roster_csv.clone();
+    }
+    if args.split_by_gender {
+        config.split_by_gender = true;
+    }
+    if args.combine_ages {
+        config.combine_ages = true;
+    }
+    if args.combine_ages_only {
+        config.combine_ages_only = true;
+    }
+    if args.events_from_meets {
+        config.events_from_meets = true;
     }
 }
 
-fn parse_meet_file(file_path: &Path) -> Result<Vec<MeetResult>, Box<dyn std::error::Error>> {
-    let filename = file_path.file_name()
-        .and_then(|n| n.to_str())
-        .ok_or("Invalid filename")?;
+fn main() -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let args = Args::parse();
     
-    let filename_clean = filename
-        .replace(".xlsx", "")
-        .replace(".xls", "");
-    let parts: Vec<&str> = filename_clean.split('_').collect();
+    let log_level = if args.verbose {
+        LevelFilter::Debug
+    } else if args.quiet {
+        LevelFilter::Warn
+    } else {
+        LevelFilter::Info
+    };
+    env_logger::Builder::new()
+        .filter_level(log_level)
+        .format_timestamp(None)
+        .format_target(false)
+        .init();
     
-    if parts.len() < 5 {
-        return Err(format!("Cannot parse filename: {}", filename).into());
-    }
+    let mut config = match &args.config {
+        Some(path) => Config::load(path)?,
+        None => Config::default(),
+    };
+    apply_args(&mut config, &args);
     
-    let course = parts[2].to_string();
-    let sex = parts[3].to_string();
-    
-    // Parse age range (format: XX-YY where YY is the age we want)
-    let age_range = parts[4];
-    let age_parts: Vec<&str> = age_range.split('-').collect();
-    if age_parts.len() != 2 {
-        return Err(format!("Invalid age range format: {}", age_range).into());
+    // The count history is a workbook sheet
+    if config.append && config.format != OutputFormat::Xlsx {
+        warn!("--append only applies to --format xlsx; ignoring it");
     }
-    let age = age_parts[1].to_string(); // Get the YY part (e.g., "12" from "00-12")
-    
-    println!("  Parsing file: {} -> Sex: {}, Age: {}, Course: {}", filename, sex, age, course);
     
-    let mut workbook: Xlsx<_> = open_workbook(file_path)?;
-    let sheet_names: Vec<String> = workbook.sheet_names().iter().map(|s| s.to_string()).collect();
+    // Show current directory for debugging
+    let current_dir = std::env::current_dir()?;
+    debug!("Running from: {:?}", current_dir);
     
-    let mut results = Vec::new();
-    let mut results_count = 0;
+    // Check if standards file exists
+    let standards_path = config.standards.as_path();
+    let full_path = current_dir.join(standards_path);
+    debug!("Looking for standards file at: {:?}", full_path);
     
-    for sheet_name in &sheet_names {
-        let event = match normalize_event_name(sheet_name) {
-            Some(e) => e,
-            None => continue,
-        };
-        
-        if let Ok(range) = workbook.worksheet_range(sheet_name) {
-            for row in range.rows() {
-                if row.len() <= 9 {
-                    continue;
+    if !standards_path.exists() {
+        // List files in current directory to help debug
+        info!("Files in current directory:");
+        if let Ok(entries) = std::fs::read_dir(&current_dir) {
+            for entry in entries.flatten() {
+                if let Ok(file_type) = entry.file_type() {
+                    let prefix = if file_type.is_dir() { "[DIR] " } else { "" };
+                    info!("  {}{}", prefix, entry.file_name().to_string_lossy());
                 }
-                
-                // Column J (index 9) for times
-                let time_seconds = match time_to_seconds(&row[9]) {
-                    Some(t) if t > 0.0 => t,
-                    _ => continue,
-                };
-                
-                // Column E (index 4) for names
-                let name = if row.len() > 4 {
-                    match &row[4] {
-                        Data::String(s) if !s.trim().is_empty() => s.trim().to_string(),
-                        _ => String::new(),
-                    }
-                } else {
-                    String::new()
-                };
-                
-                results.push(MeetResult {
-                    course: course.clone(),
-                    sex: sex.clone(),
-                    age: age.clone(),
-                    event: event.clone(),
-                    time: time_seconds,
-                    name: name.clone(),
-                });
-                results_count += 1;
             }
         }
+        return Err(AnalysisError::StandardsNotFound(standards_path.to_path_buf()).into());
     }
     
-    println!("    -> Found {} results", results_count);
-    
-    Ok(results)
-}
-
-fn load_time_standards(standards_file: &Path) -> Result<(HashMap<String, EventStandards>, HashMap<String, Vec<String>>), Box<dyn std::error::Error>> {
-    let mut workbook: Xlsx<_> = open_workbook(standards_file)?;
-    let mut all_standards: HashMap<String, EventStandards> = HashMap::new();
-    let mut event_orders: HashMap<String, Vec<String>> = HashMap::new();
-    
-    // Process both Mens and Womens tabs
-    for gender in &["Mens", "Womens"] {
-        let mut standards: EventStandards = HashMap::new();
-        let mut event_order: Vec<String> = Vec::new();
-        
-        if let Ok(range) = workbook.worksheet_range(gender) {
-            let mut age_groups: Vec<String> = Vec::new();
-            
-            // Read header row to get age groups (columns B onwards)
-            if let Some(header_row) = range.rows().next() {
-                println!("\nDEBUG: Processing {} tab", gender);
-                println!("  Header row cells:");
-                for (idx, cell) in header_row.iter().enumerate() {
-                    let cell_str = match cell {
-                        Data::String(s) => s.clone(),
-                        Data::Int(i) => i.to_string(),
-                        Data::Float(f) => f.to_string(),
-                        Data::Empty => "(empty)".to_string(),
-                        _ => format!("{:?}", cell),
-                    };
-                    println!("    Column {}: '{}'", idx, cell_str);
-                }
-                
-                for cell in header_row.iter().skip(1) {
-                    let age_str = match cell {
-                        Data::String(s) => s.trim().to_string(),
-                        Data::Int(i) => i.to_string(),
-                        Data::Float(f) => f.to_string(),
-                        _ => String::new(),
-                    };
-                    
-                    if !age_str.is_empty() {
-                        age_groups.push(normalize_age(&age_str));
-                    }
-                }
-            }
-            
-            println!("  Age groups found: {:?}", age_groups);
-            
-            // Process data rows
-            let mut row_count = 0;
-            for row in range.rows().skip(1) {
-                if row.is_empty() {
-                    continue;
-                }
-                
-                // Column A - Event name
-                let event_str = match &row[0] {
-                    Data::String(s) => s.trim(),
-                    _ => continue,
-                };
-                
-                if event_str.is_empty() {
-                    continue;
-                }
-                
-                let normalized_event = match normalize_event_name(event_str) {
-                    Some(e) => e,
-                    None => continue,
-                };
-                
-                if row_count < 3 {
-                    println!("  Sample event row {}: '{}' -> '{}'", row_count, event_str, normalized_event);
-                }
-                
-                event_order.push(normalized_event.clone());
-                
-                // Read times for each age group (columns B onwards)
-                let mut age_standards: AgeGroupStandards = HashMap::new();
-                
-                for (idx, age_group) in age_groups.iter().enumerate() {
-                    let col_idx = idx + 1; // Skip event column
-                    if col_idx < row.len() {
-                        let cell_value = &row[col_idx];
-                        if row_count < 1 && idx < 3 {
-                            println!("    Age '{}' (col {}): cell = {:?}", age_group, col_idx, cell_value);
-                        }
-                        
-                        if let Some(time_value) = time_to_seconds(cell_value) {
-                            age_standards.insert(age_group.clone(), time_value);
-                            if row_count < 1 && idx < 3 {
-                                println!("      -> Parsed as {:.2}s", time_value);
-                            }
-                        } else if row_count < 1 && idx < 3 {
-                            println!("      -> Failed to parse");
-                        }
-                    }
-                }
-                
-                if row_count < 1 {
-                    println!("    Total ages with standards for this event: {}", age_standards.len());
-                }
-                
-                standards.insert(normalized_event, age_standards);
-                row_count += 1;
-            }
-            
-            println!("  Total events loaded: {}", row_count);
-        }
-        
-        let gender_key = if *gender == "Mens" { "Men" } else { "Women" };
-        all_standards.insert(gender_key.to_string(), standards);
-        event_orders.insert(gender_key.to_string(), event_order);
+    if args.list_events {
+        let names = standards_event_names(standards_path, &config.gender_tabs, config.standards_columns(),
+                                          &config.stroke_names)?;
+        print_event_names(&names);
+        return Ok(ExitCode::SUCCESS);
     }
     
-    Ok((all_standards, event_orders))
-}
-
-fn count_qualifiers(
-    meet_results: &[MeetResult],
-    standards: &HashMap<String, EventStandards>,
-) -> HashMap<StandardKey, usize> {
-    let mut qualifier_counts: HashMap<StandardKey, usize> = HashMap::new();
-    let mut matches_found = 0;
-    let mut no_standard_count = 0;
-    
-    for result in meet_results {
-        // Get standards for this gender
-        if let Some(gender_standards) = standards.get(&result.sex) {
-            // Get standards for this event
-            if let Some(event_standards) = gender_standards.get(&result.event) {
-                // Check if there's a qualifying time for this age
-                if let Some(&qualifying_time) = event_standards.get(&result.age) {
-                    if result.time <= qualifying_time {
-                        let key = (result.sex.clone(), result.age.clone(), result.event.clone());
-                        *qualifier_counts.entry(key).or_insert(0) += 1;
-                        matches_found += 1;
-                    }
-                } else {
-                    no_standard_count += 1;
-                }
-            }
-        }
+    // Check the remaining paths before any parsing starts
+    if let Some(previous) = config.previous_standards.as_ref().filter(|path| !path.exists()) {
+        return Err(AnalysisError::StandardsNotFound(previous.clone()).into());
     }
     
-    println!("DEBUG: Found {} qualifying times", matches_found);
-    println!("DEBUG: {} results had no matching standard", no_standard_count);
+    if let Some(missing) = config.data.iter().find(|data| !data.is_dir()) {
+        return Err(AnalysisError::DataFolderNotFound(missing.clone()).into());
+    }
     
-    qualifier_counts
-}
-
-fn count_unique_qualifiers(
-    meet_results: &[MeetResult],
-    standards: &HashMap<String, EventStandards>,
-) -> HashMap<(String, String), HashSet<String>> {
-    let mut unique_qualifiers: HashMap<(String, String), HashSet<String>> = HashMap::new();
+    if let Some(output_dir) = &config.output_dir {
+        std::fs::create_dir_all(output_dir)
+            .map_err(|source| AnalysisError::OutputFolder { path: output_dir.clone(), source })?;
+        let file_name = config.output.file_name().unwrap_or(OUTPUT_FILE.as_ref());
+        config.output = output_dir.join(file_name);
+    }
     
-    for result in meet_results {
-        if result.name.is_empty() {
-            continue;
-        }
-        
-        if let Some(gender_standards) = standards.get(&result.sex) {
-            if let Some(event_standards) = gender_standards.get(&result.event) {
-                let available_ages: Vec<String> = event_standards.keys().cloned().collect();
-                
-                // Find best matching age
-                if let Some(matched_age) = find_best_age_match(&result.age, &available_ages) {
-                    if let Some(&qualifying_time) = event_standards.get(&matched_age) {
-                        if result.time <= qualifying_time {
-                            // Use the MATCHED age, not the original age
-                            let key = (result.sex.clone(), matched_age.clone());
-                            unique_qualifiers.entry(key)
-                                .or_insert_with(HashSet::new)
-                                .insert(result.name.clone());
-                        }
-                    }
-                }
-            }
+    if let Some(parent) = config.output.parent() {
+        if !parent.as_os_str().is_empty() && !parent.is_dir() {
+            return Err(AnalysisError::OutputFolderNotFound(parent.to_path_buf()).into());
         }
     }
     
-    unique_qualifiers
-}
-
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Show current directory for debugging
-    let current_dir = std::env::current_dir()?;
-    println!("Running from: {:?}", current_dir);
+    info!("Loading time standards from {}...", standards_path.display());
+    // Column labels as the standards write them ("12&U"); counts still match on the bare age
+    let (mut standards, mut event_orders, age_labels) =
+        load_time_standards_cached(standards_path, &config.gender_tabs, config.standards_columns(),
+                                   &config.stroke_names, &config.required_genders, config.cache_standards)?;
     
-    // Check if standards file exists
-    let standards_path = Path::new(TIME_STANDARDS_FILE);
-    let full_path = current_dir.join(standards_path);
-    println!("Looking for standards file at: {:?}", full_path);
+    // Same tab mapping and checks as the current standards
+    let mut previous_standards = match &config.previous_standards {
+        Some(path) => {
+            info!("Loading previous time standards from {}...", path.display());
+            let (previous, _, _) = load_time_standards_cached(path, &config.gender_tabs, config.standards_columns(),
+                                                           &config.stroke_names, &config.required_genders,
+                                                           config.cache_standards)?;
+            Some(previous)
+        }
+        None => None,
+    };
     
-    if !standards_path.exists() {
-        // List files in current directory to help debug
-        println!("\nFiles in current directory:");
-        if let Ok(entries) = std::fs::read_dir(&current_dir) {
-            for entry in entries.flatten() {
-                if let Ok(file_type) = entry.file_type() {
-                    let prefix = if file_type.is_dir() { "[DIR] " } else { "" };
-                    println!("  {}{}", prefix, entry.file_name().to_string_lossy());
-                }
+    // Event filter: names must match the normalized form used in the standards
+    if !config.events.is_empty() {
+        for event in &config.events {
+            let known = event_orders.values().flatten().any(|e| e.eq_ignore_ascii_case(event));
+            if !known {
+                warn!("--filter-event {} matches no event in the standards (expected names like 50Bu, 200Me)", event);
             }
         }
-        return Err(format!("Time standards file not found: {}", TIME_STANDARDS_FILE).into());
+        for event_order in event_orders.values_mut() {
+            event_order.retain(|e| config.includes_event(e));
+        }
     }
     
-    println!("Loading time standards from {}...", TIME_STANDARDS_FILE);
-    let (standards, event_orders) = load_time_standards(Path::new(TIME_STANDARDS_FILE))?;
+    // Only the row order changes; counts are still looked up by event name
+    for event_order in event_orders.values_mut() {
+        sort_events(event_order, config.event_sort);
+    }
     
     for (gender, gender_standards) in &standards {
-        println!("Loaded {} events for {}", gender_standards.len(), gender);
+        info!("Loaded {} events for {}", gender_standards.len(), gender);
         
         // Show what ages are in the standards
         let mut std_ages: HashSet<String> = HashSet::new();
@@ -424,170 +457,608 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
         }
         let mut std_ages_vec: Vec<_> = std_ages.into_iter().collect();
-        std_ages_vec.sort_by_key(|a| a.parse::<i32>().unwrap_or(999));
-        println!("  Ages in standards: {:?}", std_ages_vec);
+        std_ages_vec.sort_by_key(|a| age_sort_key(a));
+        debug!("  Ages in standards: {:?}", std_ages_vec);
         
         // Show sample events
         let sample_events: Vec<_> = gender_standards.keys().take(5).collect();
-        println!("  Sample events: {:?}", sample_events);
-    }
-    
-    println!("\nSearching for meet files in {}...", DATA_FOLDER);
-    
-    // Check if data folder exists
-    if !Path::new(DATA_FOLDER).exists() {
-        return Err(format!("Data folder not found: {}", DATA_FOLDER).into());
+        debug!("  Sample events: {:?}", sample_events);
     }
     
+    let mut warnings: Vec<Warning> = Vec::new();
     let mut meet_files = Vec::new();
+    // Filename -> folder it was first found in
+    let mut seen_files: HashMap<String, PathBuf> = HashMap::new();
     
-    for entry in std::fs::read_dir(DATA_FOLDER)? {
-        let entry = entry?;
-        let path = entry.path();
-        if let Some(filename) = path.file_name().and_then(|n| n.to_str()) {
-            if filename.starts_with("CAN-MBSK_") && 
-               (filename.ends_with(".xlsx") || filename.ends_with(".xls")) {
-                meet_files.push(path);
+    for data in &config.data {
+        info!("Searching for meet files in {}...", data.display());
+        
+        // Sorted so the same copy wins on every run
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(data)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<Result<_, _>>()?;
+        paths.sort();
+        
+        for path in paths {
+            if let Some(filename) = path.file_name().and_then(|n| n.to_str()) {
+                if config.team_prefixes.iter().any(|prefix| filename.starts_with(prefix.as_str())) && 
+                   (filename.ends_with(".xlsx") || filename.ends_with(".xls") || filename.ends_with(".csv")) {
+                    // The same meet copied into two folders would be counted twice
+                    if let Some(first) = seen_files.get(filename) {
+                        let message = format!("skipped, already read from {}", first.display());
+                        let warning = Warning::new(WarningCategory::DuplicateFile, message)
+                            .in_file(path.display().to_string());
+                        record_warning(&mut warnings, Level::Warn, warning);
+                        continue;
+                    }
+                    seen_files.insert(filename.to_string(), data.clone());
+                    meet_files.push(path);
+                }
             }
         }
     }
     
-    println!("Found {} meet files", meet_files.len());
+    info!("Found {} meet files", meet_files.len());
+    
+    // Sorted by filename across folders so a sample picks the same files every run
+    if let Some(sample) = args.sample {
+        meet_files.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
+        let skipped = meet_files.len().saturating_sub(sample);
+        meet_files.truncate(sample);
+        warn!("Sampling the first {} meet files (--sample); {} skipped", meet_files.len(), skipped);
+    }
     
     if meet_files.is_empty() {
-        return Err("No meet files found!".into());
+        return Err(AnalysisError::NoMeetFiles {
+            data: config.data.clone(),
+            prefixes: config.team_prefixes.clone(),
+        }.into());
     }
     
-    println!("\nParsing meet files...");
+    info!("Parsing meet files...");
     let mut all_results = Vec::new();
+    // Files whose results were read, for the manifest
+    let mut read_files = Vec::new();
+    let mut undated_files = 0;
+    // Meet files left out because they couldn't be read, by AnalysisError::skip_reason
+    let mut skipped_files: BTreeMap<&str, usize> = BTreeMap::new();
+    
+    // Files done and ETA; -v already logs each file and -q wants no output
+    let progress = if args.verbose || args.quiet {
+        ProgressBar::hidden()
+    } else {
+        ProgressBar::new(meet_files.len() as u64)
+    };
+    progress.set_style(ProgressStyle::with_template("{bar:40} {pos}/{len} files (ETA {eta})")?);
     
     for file_path in &meet_files {
-        println!("  Processing {:?}...", file_path.file_name());
-        match parse_meet_file(file_path) {
-            Ok(results) => {
-                all_results.extend(results);
+        // Date range: only files with a date in their name can be placed in it
+        let in_date_range = if config.since.is_some() || config.until.is_some() {
+            match meet_date(file_path) {
+                Some(date) if config.includes_date(date) => true,
+                Some(date) => {
+                    debug!("  Skipping {:?}: meet date {} is outside the date range", file_path.file_name(), date);
+                    false
+                }
+                None => {
+                    let warning = Warning::new(WarningCategory::UndatedFile, "skipped, no meet date in the filename")
+                        .in_file(file_name(file_path));
+                    record_warning(&mut warnings, Level::Debug, warning);
+                    undated_files += 1;
+                    false
+                }
+            }
+        } else {
+            true
+        };
+        
+        if in_date_range {
+            debug!("  Processing {:?}...", file_path.file_name());
+            // The parsers log their own warnings (clock times, duplicate rows, ...),
+            // so the bar is hidden while they run instead of being torn
+            let parsed = progress.suspend(|| {
+                if file_path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("csv")) {
+                    parse_meet_csv(file_path, &config, &mut warnings)
+                } else {
+                    parse_meet_file(file_path, &config, &mut warnings)
+                }
+            });
+            match parsed {
+                Ok(results) => {
+                    all_results.extend(results);
+                    read_files.push(file_path.clone());
+                }
+                // The counts would be missing the file, so --strict stops here
+                Err(e) if config.strict => {
+                    progress.finish_and_clear();
+                    return Err(e.into());
+                }
+                Err(e) => {
+                    let category = match e {
+                        AnalysisError::ProtectedWorkbook { .. } => WarningCategory::ProtectedFile,
+                        AnalysisError::CorruptWorkbook { .. } => WarningCategory::CorruptFile,
+                        _ => WarningCategory::UnreadableFile,
+                    };
+                    let warning = Warning::new(category, format!("not parsed: {}", e)).in_file(file_name(file_path));
+                    progress.suspend(|| record_warning(&mut warnings, Level::Warn, warning));
+                    *skipped_files.entry(e.skip_reason()).or_insert(0) += 1;
+                }
             }
-            Err(e) => println!("  Error: {}", e),
         }
+        
+        progress.inc(1);
     }
+    progress.finish_and_clear();
     
-    println!("\nTotal results extracted: {}", all_results.len());
+    if undated_files > 0 {
+        warn!("Skipped {} meet files with no parseable date in the filename (--since/--until)", undated_files);
+    }
+    
+    // "3 files skipped: 1 corrupt, 2 protected", so a short file count is explained
+    if !skipped_files.is_empty() {
+        let reasons: Vec<String> = skipped_files.iter()
+            .map(|(reason, count)| format!("{} {}", count, reason))
+            .collect();
+        warn!("{} meet files skipped: {}", skipped_files.values().sum::<usize>(), reasons.join(", "));
+    }
+    
+    let unreadable_sheets = warnings.iter()
+        .filter(|w| w.category == WarningCategory::UnreadableSheet)
+        .count();
+    if unreadable_sheets > 0 {
+        warn!("{} event sheets could not be read and are missing from the counts", unreadable_sheets);
+    }
+    
+    let unparseable_times = warnings.iter()
+        .filter(|w| w.category == WarningCategory::UnparseableTime)
+        .count();
+    if unparseable_times > 0 {
+        info!("{} times could not be read and are missing from the counts (see warnings.json)", unparseable_times);
+    }
+    
+    info!("Total results extracted: {}", all_results.len());
+    
+    // Every count compares times in the standards' course, so the results are
+    // converted once here instead of by each count
+    to_standards_course(&mut all_results, config.standards_course);
+    
+    // A sex field the standards don't know would make whole files count nothing
+    let unmapped = unmapped_sexes(&all_results, &standards);
+    if !unmapped.is_empty() {
+        for (sex, results) in &unmapped {
+            let message = format!("sex '{}' matches no standards gender; {} results dropped (add it to sex_codes)",
+                                  sex, results);
+            record_warning(&mut warnings, Level::Warn, Warning::new(WarningCategory::UnmappedSex, message));
+        }
+        all_results.retain(|r| standards.contains_key(r.sex()));
+        info!("Dropped {} results with an unmapped sex", unmapped.values().sum::<usize>());
+    }
+    
+    // Age range: drop results and standards columns outside it
+    if config.min_age.is_some() || config.max_age.is_some() {
+        let unparseable = all_results.iter().filter(|r| parse_age(&r.age).is_none()).count();
+        let before = all_results.len();
+        all_results.retain(|r| age_in_range(&r.age, config.min_age, config.max_age));
+        info!("Age range kept {} of {} results", all_results.len(), before);
+        if unparseable > 0 {
+            info!("  {} results without a numeric age were excluded", unparseable);
+        }
+        
+        for event_standards in standards.values_mut()
+            .chain(previous_standards.iter_mut().flat_map(|p| p.values_mut()))
+            .flat_map(|g| g.values_mut())
+        {
+            event_standards.retain(|age, _| age_in_range(age, config.min_age, config.max_age));
+        }
+    }
+    
+    // Counted once per run here; each of these results matches no age group
+    let unparseable = unparseable_ages(&all_results);
+    if !unparseable.is_empty() {
+        warn!("{} results have an athlete age that can't be read and match no age group (-v lists the ages)",
+              unparseable.values().sum::<usize>());
+        for (age, results) in &unparseable {
+            debug!("  age '{}': {} results", age, results);
+        }
+    }
+    
+    // After the age range filter, which also trims the standards' age groups
+    apply_over_age_policy(&mut all_results, &standards, config.over_age)?;
     
     // Debug: Show sample of what we parsed
     if !all_results.is_empty() {
-        println!("\nSample results:");
+        debug!("Sample results:");
         for result in all_results.iter().take(3) {
-            println!("  Sex: {}, Age: {}, Event: {}, Time: {:.2}s", 
+            debug!("  Sex: {}, Age: {}, Event: {}, Time: {:.2}s", 
                      result.sex, result.age, result.event, result.time);
         }
     }
     
     // Debug: Show what ages and events we have
-    let mut ages: HashSet<String> = all_results.iter().map(|r| r.age.clone()).collect();
+    let ages: HashSet<String> = all_results.iter().map(|r| r.age().to_string()).collect();
     let mut ages_vec: Vec<_> = ages.iter().cloned().collect();
-    ages_vec.sort_by_key(|a| a.parse::<i32>().unwrap_or(999));
-    println!("\nAges found in meet data: {:?}", ages_vec);
-    
-    let events: HashSet<String> = all_results.iter().map(|r| r.event.clone()).collect();
-    println!("Events found in meet data: {:?}", events.iter().take(5).collect::<Vec<_>>());
-    
-    println!("\nCounting qualifiers...");
-    let qualifier_counts = count_qualifiers(&all_results, &standards);
-    let unique_qualifiers = count_unique_qualifiers(&all_results, &standards);
-    
-    println!("Found {} qualifier count entries", qualifier_counts.len());
-    
-    // Count total unique athletes per gender/age (using matched ages)
-    let mut total_athletes: HashMap<(String, String), HashSet<String>> = HashMap::new();
-    for result in &all_results {
-        if !result.name.is_empty() {
-            // Find best matching age for this result
-            if let Some(gender_standards) = standards.get(&result.sex) {
-                // Get any event to find available ages
-                if let Some((_, event_standards)) = gender_standards.iter().next() {
-                    let available_ages: Vec<String> = event_standards.keys().cloned().collect();
-                    if let Some(matched_age) = find_best_age_match(&result.age, &available_ages) {
-                        let key = (result.sex.clone(), matched_age);
-                        total_athletes.entry(key)
-                            .or_insert_with(HashSet::new)
-                            .insert(result.name.clone());
-                    }
-                }
-            }
+    ages_vec.sort_by_key(|a| age_sort_key(a));
+    debug!("Ages found in meet data: {:?}", ages_vec);
+    
+    let events: HashSet<String> = all_results.iter().map(|r| r.event().to_string()).collect();
+    debug!("Events found in meet data: {:?}", events.iter().take(5).collect::<Vec<_>>());
+    
+    info!("Comparing against {} standards with a {:.2}s tolerance", config.standards_course.code(), config.tolerance);
+    
+    // One roster over every meet, even with --per-meet
+    let mut other_outputs = Vec::new();
+    if let Some(roster_path) = &config.roster_csv {
+        let events = qualifying_events(&all_results, &standards, config.tolerance);
+        let roster = athlete_roster(events, &event_orders);
+        write_roster_csv(roster_path, &roster)?;
+        info!("Roster of {} qualified athletes saved to {}", roster.len(), roster_path.display());
+        other_outputs.push(roster_path.clone());
+    }
+    
+    // Totals for the manifest; --per-meet hands the results out below
+    let mut totals = ManifestTotals {
+        meet_files: read_files.len(),
+        results: all_results.len(),
+        ..Default::default()
+    };
+    
+    if !config.per_meet {
+        let (output_paths, qualifiers) = write_outputs(&config, None, &standards, previous_standards.as_ref(),
+                                                       &event_orders, &age_labels, &all_results,
+                                                       &mut warnings)?;
+        totals.qualifiers = qualifiers;
+        totals.warnings = warnings.len();
+        let outputs = [output_paths.as_slice(), other_outputs.as_slice()].concat();
+        save_manifest(&config, args.config.as_deref(), &read_files, &outputs, totals)?;
+        save_warnings(&config, &warnings)?;
+        info!("Analysis complete! Results saved to {}", display_paths(&output_paths));
+        return Ok(exit_code(qualifiers));
+    }
+    
+    // One output per meet, in meet name order
+    let mut meets: BTreeMap<String, Vec<MeetResult>> = BTreeMap::new();
+    for result in all_results {
+        meets.entry(result.meet().to_string()).or_default().push(result);
+    }
+    info!("Writing {} meets separately", meets.len());
+    
+    let mut qualifiers = 0;
+    let mut all_output_paths = Vec::new();
+    for (meet, results) in &meets {
+        info!("Meet {}: {} results", meet, results.len());
+        let (output_paths, meet_qualifiers) = write_outputs(&config, Some(meet.as_str()), &standards,
+                                                            previous_standards.as_ref(), &event_orders,
+                                                            &age_labels, results, &mut warnings)?;
+        info!("  Saved to {}", display_paths(&output_paths));
+        qualifiers += meet_qualifiers;
+        all_output_paths.extend(output_paths);
+    }
+    all_output_paths.extend(other_outputs);
+    totals.qualifiers = qualifiers;
+    totals.warnings = warnings.len();
+    save_manifest(&config, args.config.as_deref(), &read_files, &all_output_paths, totals)?;
+    save_warnings(&config, &warnings)?;
+    info!("Analysis complete!");
+    
+    Ok(exit_code(qualifiers))
+}
+
+// 0 when anyone qualified, NO_QUALIFIERS_EXIT when no one did, so a wrapper
+// script can flag an empty report (usually a data or naming problem)
+fn exit_code(qualifiers: usize) -> ExitCode {
+    if qualifiers == 0 {
+        warn!("No qualifiers were found for any gender; exiting with status {}", NO_QUALIFIERS_EXIT);
+        return ExitCode::from(NO_QUALIFIERS_EXIT);
+    }
+    ExitCode::SUCCESS
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().map_or_else(|| path.display().to_string(), |name| name.to_string_lossy().into_owned())
+}
+
+// Writes manifest.json next to the output: the inputs as they were when read,
+// the command line and the totals. Saved before warnings.json so a --strict
+// failure still leaves a record of the run.
+fn save_manifest(
+    config: &Config,
+    config_path: Option<&Path>,
+    meet_files: &[PathBuf],
+    outputs: &[PathBuf],
+    totals: ManifestTotals,
+) -> Result<(), AnalysisError> {
+    let manifest = Manifest {
+        version: VERSION,
+        generated: chrono::Utc::now(),
+        args: std::env::args().skip(1).collect(),
+        config: config_path.map(Path::to_path_buf),
+        standards: InputFile::read(&config.standards)?,
+        previous_standards: config.previous_standards.as_deref().map(InputFile::read).transpose()?,
+        standards_course: config.standards_course.code().to_string(),
+        tolerance: config.tolerance,
+        meet_files: meet_files.iter().map(|path| InputFile::read(path)).collect::<Result<_, _>>()?,
+        outputs: outputs.to_vec(),
+        totals,
+    };
+    let path = manifest_path(&config.output);
+    write_manifest(&path, &manifest)?;
+    info!("Run manifest saved to {}", path.display());
+    Ok(())
+}
+
+// Writes every recorded warning to warnings.json next to the output. Written
+// even when empty, so a clean run doesn't leave an older run's file behind.
+// With --strict, any warning fails the run after the file is written.
+fn save_warnings(config: &Config, warnings: &[Warning]) -> Result<(), AnalysisError> {
+    let path = warnings_path(&config.output);
+    write_warnings(&path, warnings)?;
+    info!("{} warnings recorded in {}", warnings.len(), path.display());
+    
+    if config.strict && !warnings.is_empty() {
+        return Err(AnalysisError::StrictWarnings {
+            count: warnings.len(),
+            summary: summarize_warnings(warnings),
+            path,
+        });
+    }
+    Ok(())
+}
+
+// --list-events: each mapped tab's column A as "normalized <- raw", with a
+// count of the names whose stroke isn't recognized at the end
+fn print_event_names(names: &[StandardsEventName]) {
+    let mut current_tab: Option<&str> = None;
+    for name in names {
+        if current_tab != Some(name.tab.as_str()) {
+            println!("{} (tab '{}'):", name.gender, name.tab);
+            current_tab = Some(&name.tab);
+        }
+        if name.known_stroke {
+            println!("  {:<10} <- {}", name.event, name.raw);
+        } else {
+            println!("  {:<10} <- {}  (unknown stroke)", name.event, name.raw);
         }
     }
     
-    // Create output workbook
-    let mut workbook = Workbook::new();
+    let unknown = names.iter().filter(|n| !n.known_stroke).count();
+    if unknown > 0 {
+        println!("{} events have an unknown stroke and won't match any meet sheet; \
+                  add the stroke to stroke_names in the config", unknown);
+    }
+}
+
+// "qualifier_counts.xlsx" + "Spring2024" -> "qualifier_counts_Spring2024.xlsx"
+fn meet_output_path(output: &Path, meet: &str) -> PathBuf {
+    let stem = output.file_stem().and_then(|s| s.to_str()).unwrap_or("qualifier_counts");
+    let mut file_name = format!("{}_{}", stem, meet);
+    if let Some(ext) = output.extension().and_then(|e| e.to_str()) {
+        file_name = format!("{}.{}", file_name, ext);
+    }
+    output.with_file_name(file_name)
+}
+
+// "out/qualifier_counts.xlsx" + "Women" -> "out/womens.xlsx"
+fn gender_output_path(output: &Path, gender: &str) -> PathBuf {
+    let mut file_name = gender_label(gender).to_lowercase();
+    if let Some(ext) = output.extension().and_then(|e| e.to_str()) {
+        file_name = format!("{}.{}", file_name, ext);
+    }
+    output.with_file_name(file_name)
+}
+
+fn display_paths(paths: &[PathBuf]) -> String {
+    paths.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(", ")
+}
+
+// Only `gender`'s entry of a standards map
+fn gender_standards(standards: &HashMap<String, EventStandards>, gender: &str) -> HashMap<String, EventStandards> {
+    standards.iter()
+        .filter(|(g, _)| g.as_str() == gender)
+        .map(|(g, s)| (g.clone(), s.clone()))
+        .collect()
+}
+
+// write_counts for one run, or a meet of a --per-meet run
+// (<output>_<meet>.xlsx). With --split-by-gender, one write_counts per gender
+// tab instead (mens.xlsx, mens_<meet>.xlsx, ...), each with only that
+// gender's standards and results. Returns the paths written and the number
+// of Qualifiers Detail rows across them.
+#[allow(clippy::too_many_arguments)]
+fn write_outputs(
+    config: &Config,
+    meet: Option<&str>,
+    standards: &HashMap<String, EventStandards>,
+    previous_standards: Option<&HashMap<String, EventStandards>>,
+    event_orders: &HashMap<String, Vec<String>>,
+    age_labels: &HashMap<(String, String), String>,
+    results: &[MeetResult],
+    warnings: &mut Vec<Warning>,
+) -> Result<(Vec<PathBuf>, usize), Box<dyn std::error::Error>> {
+    let output_path = |output: PathBuf| match meet {
+        Some(meet) => meet_output_path(&output, meet),
+        None => output,
+    };
     
-    // Process each gender
-    for gender in &["Men", "Women"] {
-        let sheet_name = if *gender == "Men" { "Mens" } else { "Womens" };
-        let sheet = workbook.add_worksheet();
-        sheet.set_name(sheet_name)?;
-        
-        // Get standards and event order for this gender
-        let gender_standards = match standards.get(*gender) {
-            Some(s) => s,
-            None => continue,
-        };
-        
-        let event_order = match event_orders.get(*gender) {
-            Some(o) => o,
-            None => continue,
-        };
+    if !config.split_by_gender {
+        let mut run_config = config.clone();
+        run_config.output = output_path(config.output.clone());
+        let (path, qualifiers) = write_counts(&run_config, standards, previous_standards, event_orders,
+                                              age_labels, results, warnings)?;
+        return Ok((vec![path], qualifiers));
+    }
+    
+    let mut genders: Vec<&String> = standards.keys().collect();
+    genders.sort();
+    
+    let mut paths = Vec::new();
+    let mut qualifiers = 0;
+    for gender in genders {
+        let mut gender_config = config.clone();
+        gender_config.output = output_path(gender_output_path(&config.output, gender));
+        let gender_results: Vec<MeetResult> = results.iter().filter(|r| r.sex() == *gender).cloned().collect();
+        debug!("Writing {} {} results to {}", gender_results.len(), gender, gender_config.output.display());
         
-        // Collect all age groups
-        let mut age_groups: HashSet<String> = HashSet::new();
-        for event_standards in gender_standards.values() {
-            for age in event_standards.keys() {
-                age_groups.insert(age.clone());
-            }
+        let previous = previous_standards.map(|p| gender_standards(p, gender));
+        let (path, gender_qualifiers) = write_counts(&gender_config, &gender_standards(standards, gender),
+                                                     previous.as_ref(), event_orders, age_labels,
+                                                     &gender_results, warnings)?;
+        paths.push(path);
+        qualifiers += gender_qualifiers;
+    }
+    Ok((paths, qualifiers))
+}
+
+// Heading of this run's Count History column: the latest meet date in the
+// filenames, or today's date when no file has one
+fn history_column(results: &[MeetResult]) -> String {
+    let date = results.iter()
+        .filter_map(|r| r.date)
+        .max()
+        .unwrap_or_else(|| chrono::Local::now().date_naive());
+    date.format("%Y-%m-%d").to_string()
+}
+
+// Count grids for one set of results
+fn count_report(
+    standards: &HashMap<String, EventStandards>,
+    event_orders: &HashMap<String, Vec<String>>,
+    age_labels: &HashMap<(String, String), String>,
+    results: &[MeetResult],
+    tolerance: f64,
+) -> QualifierReport {
+    let qualifier_counts = count_qualifiers(results, standards, tolerance);
+    let athlete_counts = count_best_time_qualifiers(results, standards, tolerance);
+    let unique_qualifiers = count_unique_qualifiers(results, standards, tolerance);
+    let level_counts = count_cut_levels(results, standards, tolerance);
+    let level_athlete_counts = count_best_time_cut_levels(results, standards, tolerance);
+    
+    info!("Found {} qualifier count entries", qualifier_counts.len());
+    
+    let total_athletes = count_total_athletes(results, standards);
+    // The standards' own label ("12&U") wins over a range-fed one ("11-12")
+    let mut labels = age_group_labels(results, standards);
+    labels.extend(age_labels.iter().map(|(key, label)| (key.clone(), label.clone())));
+    build_report(
+        standards,
+        event_orders,
+        &qualifier_counts,
+        &athlete_counts,
+        &level_counts,
+        &level_athlete_counts,
+        &unique_qualifiers,
+        &total_athletes,
+        &labels,
+    )
+}
+
+// Counts `all_results` against the standards and writes the workbook (or JSON)
+// to config.output. Unmatched results are added to `warnings`. Returns the path
+// actually written and the number of Qualifiers Detail rows.
+fn write_counts(
+    config: &Config,
+    standards: &HashMap<String, EventStandards>,
+    previous_standards: Option<&HashMap<String, EventStandards>>,
+    event_orders: &HashMap<String, Vec<String>>,
+    age_labels: &HashMap<(String, String), String>,
+    all_results: &[MeetResult],
+    warnings: &mut Vec<Warning>,
+) -> Result<(PathBuf, usize), Box<dyn std::error::Error>> {
+    info!("Counting qualifiers...");
+    let tolerance = config.tolerance;
+    let qualifiers = qualifier_details(all_results, standards, tolerance);
+    let near_misses = count_near_qualifiers(all_results, standards, tolerance, config.near_miss_pct);
+    info!("Found {} near misses within {}% of the cut", near_misses.len(), config.near_miss_pct);
+    let rankings = rankings(all_results, standards, tolerance, config.top_n);
+    let multi_event = multi_event_qualifiers(&count_qualifying_events(all_results, standards, tolerance));
+    info!("{} athletes qualified in more than one event", multi_event.len());
+    let participation = participation(all_results);
+    let best_times = if config.best_vs_cut {
+        best_vs_cut(all_results, standards)
+    } else {
+        Vec::new()
+    };
+    
+    // Usually a naming mismatch between the meet files and the standards
+    let unmatched = unmatched_results(all_results, standards);
+    if !unmatched.is_empty() {
+        let dropped: usize = unmatched.iter().map(|key| key.results).sum();
+        info!("{} results in {} sex/event/age groups had no matching standard",
+              dropped, unmatched.len());
+        for key in &unmatched {
+            let message = format!("no standard for {} {} age {} ({} results)", key.sex, key.event, key.age, key.results);
+            record_warning(warnings, Level::Debug, Warning::new(WarningCategory::UnmatchedEvent, message));
         }
-        
-        let mut age_groups_vec: Vec<String> = age_groups.into_iter().collect();
-        age_groups_vec.sort_by_key(|a| a.parse::<i32>().unwrap_or(999));
-        
-        // Write headers
-        sheet.write_string(0, 0, "Event")?;
-        for (i, age) in age_groups_vec.iter().enumerate() {
-            sheet.write_string(0, (i + 1) as u16, age)?;
+    }
+    // With --events-from-meets the grids list the events that have no standard too
+    let event_orders = if config.events_from_meets {
+        with_meet_events(event_orders, standards, &unmatched)
+    } else {
+        event_orders.clone()
+    };
+    
+    let changes = match previous_standards {
+        Some(previous) => {
+            let changes = status_changes(all_results, previous, standards, tolerance);
+            let newly_qualified = changes.iter().filter(|c| c.newly_qualified).count();
+            info!("Against the previous standards: {} newly qualified, {} no longer qualified",
+                  newly_qualified, changes.len() - newly_qualified);
+            changes
         }
-        
-        // Write data rows following event order
-        let mut row = 1u32;
-        for event in event_order {
-            sheet.write_string(row, 0, event)?;
-            
-            for (col, age) in age_groups_vec.iter().enumerate() {
-                let key = (gender.to_string(), age.clone(), event.clone());
-                let count = qualifier_counts.get(&key).copied().unwrap_or(0);
-                sheet.write_number(row, (col + 1) as u16, count as f64)?;
+        None => Vec::new(),
+    };
+    
+    // Qualifying swims the swim counts miss but the unique athlete counts include
+    let mismatches = age_mismatches(all_results, standards, tolerance);
+    if !mismatches.is_empty() {
+        info!("{} qualifying swims had no standard for their exact age (see the Age Mismatch sheet)",
+              mismatches.len());
+    }
+    
+    // Mixed courses are counted separately, one set of count sheets per course
+    let courses = results_by_course(all_results);
+    let mut report = if courses.len() > 1 {
+        info!("Meet files mix {} courses; writing the counts per course", courses.len());
+        let mut genders = Vec::new();
+        for (code, results) in &courses {
+            let course_report = count_report(standards, &event_orders, age_labels, results, tolerance);
+            for mut gender_report in course_report.genders {
+                gender_report.course = Some(code.clone());
+                genders.push(gender_report);
             }
-            
-            row += 1;
         }
-        
-        // Add summary rows
-        row += 1;
-        sheet.write_string(row, 0, "Total Unique Athletes")?;
-        for (col, age) in age_groups_vec.iter().enumerate() {
-            let key = (gender.to_string(), age.clone());
-            let count = total_athletes.get(&key).map(|s| s.len()).unwrap_or(0);
-            sheet.write_number(row, (col + 1) as u16, count as f64)?;
+        QualifierReport { genders }
+    } else {
+        count_report(standards, &event_orders, age_labels, all_results, tolerance)
+    };
+    if config.combine_ages || config.combine_ages_only {
+        combine_ages(&mut report, !config.combine_ages_only);
+    }
+    
+    match config.format {
+        OutputFormat::Xlsx => {
+            let history = if config.append {
+                let mut history = read_count_history(&config.output)?;
+                let column = history_column(all_results);
+                append_counts(&mut history, &column, &report);
+                info!("Count history: added '{}' ({} columns, {} rows)", column, history.columns.len(),
+                      history.rows.len());
+                Some(history)
+            } else {
+                None
+            };
+            write_workbook(config, &report, &qualifiers, &near_misses, &rankings, &best_times, &changes,
+                           &unmatched, &mismatches, &multi_event, &participation, history.as_ref())?;
+            Ok((config.output.clone(), qualifiers.len()))
         }
-        
-        row += 1;
-        sheet.write_string(row, 0, "Unique Qualifiers")?;
-        for (col, age) in age_groups_vec.iter().enumerate() {
-            let key = (gender.to_string(), age.clone());
-            let count = unique_qualifiers.get(&key).map(|s| s.len()).unwrap_or(0);
-            sheet.write_number(row, (col + 1) as u16, count as f64)?;
+        OutputFormat::Json => {
+            let json_path = config.output.with_extension("json");
+            write_json(&json_path, &report)?;
+            Ok((json_path, qualifiers.len()))
+        }
+        OutputFormat::Markdown => {
+            let md_path = config.output.with_extension("md");
+            write_markdown(&md_path, &report, config.count_mode)?;
+            Ok((md_path, qualifiers.len()))
         }
     }
-    
-    workbook.save(OUTPUT_FILE)?;
-    println!("\nAnalysis complete! Results saved to {}", OUTPUT_FILE);
-    
-    Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,74 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::error::AnalysisError;
+use crate::output::write_atomic;
+
+// The crate version recorded in each manifest
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// Record of what produced an output, written as manifest.json next to it so a
+// report can be traced back to its inputs and settings later
+#[derive(Debug, Serialize)]
+pub struct Manifest {
+    pub version: &'static str, // Crate version that wrote the output
+    pub generated: DateTime<Utc>,
+    pub args: Vec<String>, // Command line as given, without the program name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config: Option<PathBuf>, // --config file, when one was used
+    pub standards: InputFile,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_standards: Option<InputFile>,
+    pub standards_course: String,
+    pub tolerance: f64,
+    // Meet files whose results were read, in the order they were parsed
+    pub meet_files: Vec<InputFile>,
+    pub outputs: Vec<PathBuf>,
+    pub totals: ManifestTotals,
+}
+
+// An input file as it was when the run read it
+#[derive(Debug, Clone, Serialize)]
+pub struct InputFile {
+    pub path: PathBuf,
+    pub size: u64,
+    // Last modified time; None where the filesystem doesn't keep one
+    pub modified: Option<DateTime<Utc>>,
+}
+
+impl InputFile {
+    pub fn read(path: &Path) -> Result<Self, AnalysisError> {
+        let metadata = std::fs::metadata(path)?;
+        Ok(InputFile {
+            path: path.to_path_buf(),
+            size: metadata.len(),
+            modified: metadata.modified().ok().map(DateTime::<Utc>::from),
+        })
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ManifestTotals {
+    pub meet_files: usize,
+    pub results: usize, // Results counted, after the sex and age filters
+    pub qualifiers: usize, // Qualifiers Detail rows, summed over every output
+    pub warnings: usize,
+}
+
+// "out/qualifier_counts.xlsx" -> "out/manifest.json"
+pub fn manifest_path(output: &Path) -> PathBuf {
+    output.with_file_name("manifest.json")
+}
+
+pub fn write_manifest(path: &Path, manifest: &Manifest) -> Result<(), AnalysisError> {
+    write_atomic(path, |temp| {
+        let mut writer = BufWriter::new(File::create(temp)?);
+        serde_json::to_writer_pretty(&mut writer, manifest)?;
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+        Ok(())
+    })
+}
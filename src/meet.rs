@@ -0,0 +1,778 @@
+use calamine::{open_workbook, Data, Reader, Sheets, Xls, XlsError, Xlsx, XlsxError};
+use chrono::NaiveDate;
+use log::{debug, info, Level};
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::LazyLock;
+
+use crate::age::parse_age;
+use crate::config::{Config, RoundPolicy, NAME_COLUMN, TIME_COLUMN};
+use crate::error::AnalysisError;
+use crate::normalize::{is_event_name, normalize_name};
+use crate::time::{time_to_seconds, TimeCell};
+use crate::warning::{record_warning, Warning, WarningCategory};
+
+// The filename fields and the event are shared by every result of a file (or
+// sheet), so they're reference counted rather than copied per row. The
+// accessors give them as plain &str.
+#[derive(Debug, Clone)]
+pub struct MeetResult {
+    pub folder: Rc<Path>, // Data folder the meet file was found in
+    pub meet: Rc<str>, // Meet part of the filename (CAN-MBSK_<meet>_SCY_...)
+    pub date: Option<NaiveDate>, // Meet date from the filename, when it has one
+    pub course: Rc<str>,
+    pub sex: Rc<str>,
+    pub age: Rc<str>,     // Upper age of the filename's range, used for matching
+    pub age_min: Rc<str>, // Lower age; same as age for single-age files
+    pub event: Rc<str>,
+    pub time: f64,
+    pub split_time: Option<f64>, // First SPLIT_DISTANCE split, when a split column is configured
+    pub round: Option<Round>, // Round the time is from; None when the sheet has one time column
+    pub name: String, // Team name for relays
+    pub is_relay: bool,
+}
+
+// Round of a swim on sheets with both a prelim and a final time column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Round {
+    Prelim,
+    Final,
+}
+
+impl MeetResult {
+    pub fn folder(&self) -> &Path {
+        &self.folder
+    }
+
+    pub fn meet(&self) -> &str {
+        &self.meet
+    }
+
+    pub fn course(&self) -> &str {
+        &self.course
+    }
+
+    pub fn sex(&self) -> &str {
+        &self.sex
+    }
+
+    pub fn age(&self) -> &str {
+        &self.age
+    }
+
+    pub fn age_min(&self) -> &str {
+        &self.age_min
+    }
+
+    pub fn event(&self) -> &str {
+        &self.event
+    }
+}
+
+// Fields encoded in a meet filename: PREFIX_xxx_COURSE_SEX_XX-YY[_EVENT...]
+// (or a single age in place of XX-YY)
+struct MeetFileInfo {
+    folder: Rc<Path>,
+    filename: String,
+    parts: Vec<String>,
+    meet: Rc<str>,
+    date: Option<NaiveDate>,
+    course: Rc<str>,
+    sex: Rc<str>,
+    age: Rc<str>,
+    age_min: Rc<str>,
+}
+
+// "2024-03-15" or "20240315" anywhere in a filename part
+static MEET_DATE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(\d{4})-?(\d{2})-?(\d{2})").unwrap());
+
+fn parse_date_part(part: &str) -> Option<NaiveDate> {
+    let caps = MEET_DATE.captures(part)?;
+    NaiveDate::from_ymd_opt(caps[1].parse().ok()?, caps[2].parse().ok()?, caps[3].parse().ok()?)
+}
+
+// Meet date from the first two filename parts (CAN-MBSK_2024-03-15_SCY_...).
+// None when neither holds a valid date.
+pub fn meet_date(file_path: &Path) -> Option<NaiveDate> {
+    let stem = file_path.file_stem()?.to_str()?;
+    stem.split('_').take(2).find_map(parse_date_part)
+}
+
+// Results grouped by the course in their filename, keyed by the upper-case
+// code ("LCM", "SCY") in code order
+pub fn results_by_course(results: &[MeetResult]) -> BTreeMap<String, Vec<MeetResult>> {
+    let mut courses: BTreeMap<String, Vec<MeetResult>> = BTreeMap::new();
+    for result in results {
+        courses.entry(result.course.trim().to_ascii_uppercase()).or_default().push(result.clone());
+    }
+    courses
+}
+
+fn parse_meet_filename(file_path: &Path, config: &Config) -> Result<MeetFileInfo, AnalysisError> {
+    let filename = file_path.file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| AnalysisError::FilenameParse {
+            filename: file_path.display().to_string(),
+            reason: "not a valid file name".to_string(),
+        })?;
+
+    let filename_clean = file_path.file_stem()
+        .and_then(|n| n.to_str())
+        .unwrap_or(filename);
+    let parts: Vec<String> = filename_clean.split('_').map(|p| p.to_string()).collect();
+
+    if parts.len() < 5 {
+        return Err(AnalysisError::FilenameParse {
+            filename: filename.to_string(),
+            reason: format!("expected at least 5 '_'-separated parts, found {}", parts.len()),
+        });
+    }
+
+    let meet = parts[1].clone();
+    let course = parts[2].clone();
+    let sex = config.map_sex(&parts[3]);
+
+    // Parse age range (format: XX-YY where YY is the age we want, or a single age)
+    let age_range = &parts[4];
+    let age_parts: Vec<&str> = age_range.split('-').collect();
+    let (age_min, age) = match age_parts.as_slice() {
+        [min, max] => (min.to_string(), max.to_string()), // e.g. "12" from "00-12"
+        [single] if parse_age(single).is_some() => (single.to_string(), single.to_string()),
+        _ => return Err(AnalysisError::InvalidAgeRange(age_range.to_string())),
+    };
+
+    debug!("  Parsing file: {} -> Sex: {} ({}), Age: {}-{}, Course: {}", filename, sex, parts[3], age_min, age,
+           course);
+
+    Ok(MeetFileInfo {
+        folder: Rc::from(file_path.parent().unwrap_or(Path::new(""))),
+        filename: filename.to_string(),
+        parts,
+        meet: meet.into(),
+        date: meet_date(file_path),
+        course: course.into(),
+        sex: sex.into(),
+        age: age.into(),
+        age_min: age_min.into(),
+    })
+}
+
+// Column layout of one meet worksheet or CSV file
+struct SheetColumns {
+    time: usize,
+    name: usize,
+    event: Option<usize>,
+    split: Option<usize>,
+    prelim: Option<usize>,
+    header_row: Option<usize>,
+}
+
+// Looks for "Time"/"Name"/"Event" style labels in the first non-empty row.
+// Columns set in the config (or by --time-col/--name-col) win over the header,
+// and TIME_COLUMN/NAME_COLUMN are used when neither gives one.
+//
+// Configured columns are spreadsheet columns ("K" is K wherever the data
+// starts), so they're shifted by `first_col`, the column the rows start at. A
+// configured column left of it is empty in this sheet and is ignored.
+fn detect_columns<'a>(
+    rows: impl IntoIterator<Item = &'a [Data]>,
+    first_col: usize,
+    sheet_name: &str,
+    config: &Config,
+) -> SheetColumns {
+    let first_row = rows.into_iter()
+        .enumerate()
+        .find(|(_, row)| row.iter().any(|cell| !matches!(cell, Data::Empty)));
+
+    let find_label = |labels: &[String]| -> Option<usize> {
+        let (_, row) = first_row?;
+        row.iter().position(|cell| match cell {
+            Data::String(s) => labels.iter().any(|label| label.eq_ignore_ascii_case(s.trim())),
+            _ => false,
+        })
+    };
+
+    let in_rows = |column: usize| column.checked_sub(first_col);
+    let time_col = config.time_col.and_then(in_rows);
+    let name_col = config.name_col.and_then(in_rows);
+
+    let time_header = find_label(&config.time_headers);
+    let name_header = find_label(&config.name_headers);
+    let event_header = find_label(&config.event_headers);
+
+    let source = |configured: Option<usize>, header: Option<usize>| {
+        if configured.is_some() {
+            "configured"
+        } else if header.is_some() {
+            "header"
+        } else {
+            "default"
+        }
+    };
+
+    let columns = SheetColumns {
+        time: time_col.or(time_header).unwrap_or(TIME_COLUMN),
+        name: name_col.or(name_header).unwrap_or(NAME_COLUMN),
+        event: event_header,
+        split: config.split_col.and_then(in_rows),
+        prelim: config.prelim_col.and_then(in_rows),
+        header_row: if time_header.is_some() || name_header.is_some() || event_header.is_some() {
+            first_row.map(|(idx, _)| idx)
+        } else {
+            None
+        },
+    };
+
+    debug!("    Sheet '{}': time column {} ({}), name column {} ({})",
+           sheet_name,
+           columns.time, source(time_col, time_header),
+           columns.name, source(name_col, name_header));
+    if let Some(prelim) = columns.prelim {
+        debug!("    Sheet '{}': prelim time column {} ({:?} policy)", sheet_name, prelim, config.round_policy);
+    }
+
+    columns
+}
+
+// Labels that mark a repeated header or section row rather than a swim, on top
+// of the configured name/time headers
+const ROW_LABELS: &[&str] = &["Place", "Rank"];
+
+fn is_label(cell: Option<&Data>, headers: &[String]) -> bool {
+    match cell {
+        Some(Data::String(s)) => {
+            let s = s.trim();
+            headers.iter().any(|h| h.eq_ignore_ascii_case(s))
+                || ROW_LABELS.iter().any(|label| label.eq_ignore_ascii_case(s))
+        }
+        _ => false,
+    }
+}
+
+// Per-file counts of rows that did or didn't yield a result
+#[derive(Default)]
+struct RowTally {
+    results: usize,
+    status_counts: HashMap<String, usize>,
+    unparseable: usize,
+    label_rows: usize,
+    clock_times: usize,
+    prelim_times: usize, // Results whose time came from the prelim column
+}
+
+impl RowTally {
+    fn log(&self, info: &MeetFileInfo, warnings: &mut Vec<Warning>) {
+        debug!("    -> Found {} results", self.results);
+
+        // Shown by default so DQs and scratches (real swims without a time) can
+        // be told apart from times that couldn't be read
+        if !self.status_counts.is_empty() {
+            let mut codes: Vec<_> = self.status_counts.iter().collect();
+            codes.sort();
+            let summary: Vec<String> = codes.iter()
+                .map(|(code, count)| format!("{} {}", count, code))
+                .collect();
+            info!("{}: not timed: {}", info.filename, summary.join(", "));
+        }
+
+        if self.unparseable > 0 {
+            info!("{}: {} times could not be read", info.filename, self.unparseable);
+        }
+
+        if self.label_rows > 0 {
+            debug!("    -> {} header/junk rows skipped", self.label_rows);
+        }
+
+        if self.prelim_times > 0 {
+            debug!("    -> {} times taken from prelims", self.prelim_times);
+        }
+
+        if self.clock_times > 0 {
+            let message = format!("{} times were stored as clock times (e.g. 12:01:32 PM); read as minutes and seconds",
+                                  self.clock_times);
+            record_warning(warnings, Level::Warn, sheet_warning(info, None, WarningCategory::ClockTime, message));
+        }
+    }
+}
+
+// Finds exact repeats of (name, event, time) among results[start..], i.e. one
+// sheet's worth, and drops them when `dedup` is set. Returns how many were found.
+fn find_duplicates(results: &mut Vec<MeetResult>, start: usize, dedup: bool) -> usize {
+    let mut seen: HashSet<(String, Rc<str>, u64)> = HashSet::new();
+    let mut duplicates = 0;
+    
+    let sheet: Vec<MeetResult> = results.drain(start..).collect();
+    for result in sheet {
+        if !seen.insert((result.name.clone(), result.event.clone(), result.time.to_bits())) {
+            duplicates += 1;
+            if dedup {
+                continue;
+            }
+        }
+        results.push(result);
+    }
+    
+    duplicates
+}
+
+fn log_duplicates(
+    info: &MeetFileInfo,
+    sheet: Option<&str>,
+    duplicates: usize,
+    dedup: bool,
+    warnings: &mut Vec<Warning>,
+) {
+    if duplicates > 0 {
+        let message = format!("{} duplicate results (same name, event and time){}",
+                              duplicates, if dedup { ", removed" } else { "; use --dedup to remove" });
+        record_warning(warnings, Level::Warn, sheet_warning(info, sheet, WarningCategory::DuplicateRows, message));
+    }
+}
+
+// Warning located in a meet file, and in one of its sheets when `sheet` is
+// given (CSV files have none)
+fn sheet_warning(info: &MeetFileInfo, sheet: Option<&str>, category: WarningCategory, message: String) -> Warning {
+    let warning = Warning::new(category, message).in_file(&info.filename);
+    match sheet {
+        Some(sheet) => warning.in_sheet(sheet),
+        None => warning,
+    }
+}
+
+// Picks the time of a row with a final and a prelim cell. A round without a
+// swim falls back to the other; when neither was swum, the final's status (or
+// the prelim's, when the final is blank) is what gets tallied.
+fn pick_round(final_time: TimeCell, prelim_time: TimeCell, policy: RoundPolicy) -> (TimeCell, Round) {
+    let swum = |cell: &TimeCell| cell.seconds().is_some_and(|t| t > 0.0);
+    match (swum(&final_time), swum(&prelim_time)) {
+        (true, true) => {
+            let use_prelim = match policy {
+                RoundPolicy::Best => prelim_time.seconds() < final_time.seconds(),
+                RoundPolicy::Final => false,
+                RoundPolicy::Prelim => true,
+            };
+            if use_prelim {
+                (prelim_time, Round::Prelim)
+            } else {
+                (final_time, Round::Final)
+            }
+        }
+        (true, false) => (final_time, Round::Final),
+        (false, true) => (prelim_time, Round::Prelim),
+        (false, false) if matches!(final_time, TimeCell::Empty) => (prelim_time, Round::Prelim),
+        (false, false) => (final_time, Round::Final),
+    }
+}
+
+// Rows of one sheet (or CSV file) that didn't yield a result
+#[derive(Default)]
+struct SkippedRows {
+    label_rows: usize,
+    short_rows: usize,
+    unparseable_times: Vec<(usize, String)>, // (row index in the sheet's rows, cell text)
+}
+
+// Turns the rows of one sheet (or CSV file) into results. `event_for_row`
+// supplies the normalized event and relay flag, or None to skip the row. Rows
+// can be borrowed from a sheet's range or owned as they're read from a CSV.
+fn read_result_rows<R: AsRef<[Data]>>(
+    rows: impl IntoIterator<Item = R>,
+    columns: &SheetColumns,
+    info: &MeetFileInfo,
+    config: &Config,
+    mut event_for_row: impl FnMut(&[Data]) -> Option<(Rc<str>, bool)>,
+    tally: &mut RowTally,
+    results: &mut Vec<MeetResult>,
+) -> SkippedRows {
+    let mut skipped = SkippedRows::default();
+    
+    for (row_idx, row) in rows.into_iter().enumerate() {
+        let row = row.as_ref();
+        if Some(row_idx) == columns.header_row {
+            continue;
+        }
+        
+        // A configured column past the end of the row; the name is left blank
+        // when only the name column is missing
+        let has_content = row.iter().any(|cell| !matches!(cell, Data::Empty));
+        if has_content && row.len() <= columns.time.max(columns.name) {
+            skipped.short_rows += 1;
+        }
+        if row.len() <= columns.time {
+            continue;
+        }
+        
+        // Header rows repeated mid-sheet ("Name" ... "Time") aren't swims
+        if is_label(row.get(columns.name), &config.name_headers)
+            || is_label(row.get(columns.time), &config.time_headers)
+        {
+            skipped.label_rows += 1;
+            continue;
+        }
+
+        // Column J (index 9) unless a header says otherwise; with a prelim
+        // column, round_policy picks between the two
+        let final_time = time_to_seconds(&row[columns.time]);
+        let (time_cell, round) = match columns.prelim {
+            Some(col) => {
+                let prelim_time = row.get(col).map_or(TimeCell::Empty, time_to_seconds);
+                let (cell, round) = pick_round(final_time, prelim_time, config.round_policy);
+                (cell, Some(round))
+            }
+            None => (final_time, None),
+        };
+        let time_col = match (round, columns.prelim) {
+            (Some(Round::Prelim), Some(col)) => col,
+            _ => columns.time,
+        };
+
+        let time_seconds = match time_cell {
+            TimeCell::Time(t) if t > 0.0 => t,
+            TimeCell::ClockTime(t) if t > 0.0 => {
+                tally.clock_times += 1;
+                t
+            }
+            TimeCell::Status(code) => {
+                *tally.status_counts.entry(code).or_insert(0) += 1;
+                continue;
+            }
+            TimeCell::Unparseable => {
+                tally.unparseable += 1;
+                skipped.unparseable_times.push((row_idx, row[time_col].to_string()));
+                continue;
+            }
+            _ => continue,
+        };
+
+        let (event, is_relay) = match event_for_row(row) {
+            Some(e) => e,
+            None => continue,
+        };
+
+        // Only read when configured; a blank or unreadable split is just missing
+        let split_time = columns.split
+            .and_then(|col| row.get(col))
+            .and_then(|cell| match time_to_seconds(cell) {
+                TimeCell::Time(t) if t > 0.0 => Some(t),
+                _ => None,
+            });
+
+        // Column E (index 4) unless a header says otherwise (team name for relays)
+        let name = if row.len() > columns.name {
+            match &row[columns.name] {
+                // Names are dedup keys, so spelling variants collapse here
+                Data::String(s) if !s.trim().is_empty() => {
+                    normalize_name(s, config.reorder_names && !is_relay)
+                }
+                _ => String::new(),
+            }
+        } else {
+            String::new()
+        };
+
+        results.push(MeetResult {
+            folder: info.folder.clone(),
+            meet: info.meet.clone(),
+            date: info.date,
+            course: info.course.clone(),
+            sex: info.sex.clone(),
+            age: info.age.clone(),
+            age_min: info.age_min.clone(),
+            event,
+            time: time_seconds,
+            split_time,
+            round,
+            name,
+            is_relay,
+        });
+        tally.results += 1;
+        if round == Some(Round::Prelim) {
+            tally.prelim_times += 1;
+        }
+    }
+    
+    tally.label_rows += skipped.label_rows;
+    skipped
+}
+
+// Records the rows read_result_rows couldn't use. `first_row` is the 0-based
+// spreadsheet row the sheet's rows start at, for 1-based row numbers.
+fn record_skipped_rows(
+    info: &MeetFileInfo,
+    sheet: Option<&str>,
+    columns: &SheetColumns,
+    skipped: &SkippedRows,
+    first_row: usize,
+    warnings: &mut Vec<Warning>,
+) {
+    if skipped.short_rows > 0 {
+        let message = format!("{} rows end before the time column ({}) or name column ({}); check --time-col/--name-col",
+                              skipped.short_rows, columns.time, columns.name);
+        record_warning(warnings, Level::Warn, sheet_warning(info, sheet, WarningCategory::ShortRows, message));
+    }
+    
+    // Already summed up per file by RowTally, so only -v logs each row
+    for (row_idx, cell) in &skipped.unparseable_times {
+        let message = format!("time '{}' could not be read", cell);
+        let warning = sheet_warning(info, sheet, WarningCategory::UnparseableTime, message)
+            .at_row(first_row + row_idx + 1);
+        record_warning(warnings, Level::Debug, warning);
+    }
+}
+
+// First bytes of each workbook format: .xlsx is a zip archive, legacy BIFF
+// .xls an OLE compound file
+const XLSX_SIGNATURE: &[u8] = b"PK\x03\x04";
+const XLS_SIGNATURE: &[u8] = &[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+
+// Opens a meet workbook with the reader for its extension (Xls for .xls, Xlsx
+// otherwise). Fails with WorkbookFormat when the contents are the other
+// format, e.g. an .xlsx export renamed to .xls; anything unrecognized is left
+// to the reader's own error.
+fn open_meet_workbook(file_path: &Path) -> Result<Sheets<BufReader<File>>, AnalysisError> {
+    let is_xls = file_path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("xls"));
+    
+    let mut signature = Vec::new();
+    File::open(file_path)?.take(XLS_SIGNATURE.len() as u64).read_to_end(&mut signature)?;
+    let actual = if signature.starts_with(XLSX_SIGNATURE) {
+        Some("xlsx")
+    } else if signature.starts_with(XLS_SIGNATURE) {
+        Some("xls")
+    } else {
+        None
+    };
+    let expected = if is_xls { "xls" } else { "xlsx" };
+    if let Some(actual) = actual.filter(|actual| *actual != expected) {
+        // A password-protected .xlsx is saved encrypted inside an OLE file, so
+        // it starts like an .xls; the Xlsx reader tells the two apart
+        if !is_xls {
+            if let Err(XlsxError::Password) = open_workbook::<Xlsx<_>, _>(file_path) {
+                return Err(AnalysisError::ProtectedWorkbook { path: file_path.to_path_buf() });
+            }
+        }
+        return Err(AnalysisError::WorkbookFormat {
+            path: file_path.to_path_buf(),
+            extension: expected.to_string(),
+            actual: actual.to_string(),
+        });
+    }
+    
+    let open_error = |source: calamine::Error| workbook_error(file_path, source);
+    if is_xls {
+        let workbook: Xls<_> = open_workbook(file_path).map_err(|e: XlsError| open_error(e.into()))?;
+        Ok(Sheets::Xls(workbook))
+    } else {
+        let workbook: Xlsx<_> = open_workbook(file_path).map_err(|e: XlsxError| open_error(e.into()))?;
+        Ok(Sheets::Xlsx(workbook))
+    }
+}
+
+// Sorts a reader failure into a password-protected workbook, a damaged one
+// (bad zip, XML or OLE structure), or a plain I/O error
+fn workbook_error(path: &Path, source: calamine::Error) -> AnalysisError {
+    let path = path.to_path_buf();
+    match &source {
+        calamine::Error::Xlsx(XlsxError::Password) | calamine::Error::Xls(XlsError::Password) => {
+            AnalysisError::ProtectedWorkbook { path }
+        }
+        calamine::Error::Xlsx(XlsxError::Io(_)) | calamine::Error::Xls(XlsError::Io(_)) => {
+            AnalysisError::Workbook { path, source }
+        }
+        calamine::Error::Xlsx(_) | calamine::Error::Xls(_) => AnalysisError::CorruptWorkbook { path, source },
+        _ => AnalysisError::Workbook { path, source },
+    }
+}
+
+// Reads every event sheet of a meet workbook. Soft failures (skipped sheets,
+// short or duplicate rows, unreadable times) are logged and added to `warnings`.
+pub fn parse_meet_file(
+    file_path: &Path,
+    config: &Config,
+    warnings: &mut Vec<Warning>,
+) -> Result<Vec<MeetResult>, AnalysisError> {
+    let info = parse_meet_filename(file_path, config)?;
+
+    let mut workbook = open_meet_workbook(file_path)?;
+    let sheet_names: Vec<String> = workbook.sheet_names().iter().map(|s| s.to_string()).collect();
+
+    let mut results = Vec::new();
+    let mut tally = RowTally::default();
+    let mut processed = 0;
+    let mut skipped = Vec::new();
+    let mut unreadable = 0;
+
+    for sheet_name in &sheet_names {
+        if config.ignores_sheet(sheet_name) {
+            debug!("    Sheet '{}': skipped, listed in ignore_sheets", sheet_name);
+            skipped.push(sheet_name.as_str());
+            continue;
+        }
+
+        let sheet_event = match config.normalize_event(sheet_name) {
+            Some(e) if !is_event_name(&e.0, &config.stroke_names) => {
+                let message = "skipped, not an event name (add it to ignore_sheets to silence)".to_string();
+                record_warning(warnings, Level::Info,
+                               sheet_warning(&info, Some(sheet_name), WarningCategory::SkippedSheet, message));
+                skipped.push(sheet_name.as_str());
+                continue;
+            }
+            Some(e) if !config.includes_event(&e.0) => {
+                debug!("    Sheet '{}': skipped, {} is not in the event filter", sheet_name, e.0);
+                skipped.push(sheet_name.as_str());
+                continue;
+            }
+            Some((event, is_relay)) => (Rc::<str>::from(event), is_relay),
+            None => {
+                debug!("    Sheet '{}': skipped, no event name", sheet_name);
+                skipped.push(sheet_name.as_str());
+                continue;
+            }
+        };
+
+        match workbook.worksheet_range(sheet_name) {
+            // The whole sheet is loaded: calamine only streams cells for xlsx,
+            // and its ranges start at the first used cell, which the default
+            // time and name columns count from. Only one sheet's range is held
+            // at a time, and it's dropped as soon as its rows are read.
+            Ok(range) => {
+                processed += 1;
+                let sheet_start = results.len();
+                results.reserve(range.height());
+                let first_col = range.start().map_or(0, |(_, col)| col as usize);
+                let columns = detect_columns(range.rows(), first_col, sheet_name, config);
+                let skipped_rows = read_result_rows(range.rows(), &columns, &info, config,
+                                                    |_| Some(sheet_event.clone()),
+                                                    &mut tally, &mut results);
+                if skipped_rows.label_rows > 0 {
+                    debug!("    Sheet '{}': skipped {} header/junk rows", sheet_name, skipped_rows.label_rows);
+                }
+                let first_row = range.start().map_or(0, |(row, _)| row as usize);
+                drop(range);
+                record_skipped_rows(&info, Some(sheet_name), &columns, &skipped_rows, first_row, warnings);
+                
+                // Exports occasionally repeat rows, which would inflate swim counts
+                let duplicates = find_duplicates(&mut results, sheet_start, config.dedup);
+                log_duplicates(&info, Some(sheet_name), duplicates, config.dedup, warnings);
+            }
+            // A corrupt sheet would otherwise drop its swims from the totals unnoticed
+            Err(e) => {
+                let message = format!("could not read sheet, its swims are left out: {}", e);
+                record_warning(warnings, Level::Warn,
+                               sheet_warning(&info, Some(sheet_name), WarningCategory::UnreadableSheet, message));
+                unreadable += 1;
+            }
+        }
+    }
+
+    debug!("    -> {} sheets processed, {} skipped, {} unreadable", processed, skipped.len(), unreadable);
+    if processed == 0 && !skipped.is_empty() {
+        let message = format!("no event sheets found (skipped: {})", skipped.join(", "));
+        record_warning(warnings, Level::Warn, sheet_warning(&info, None, WarningCategory::NoEventSheets, message));
+    }
+    tally.log(&info, warnings);
+
+    Ok(results)
+}
+
+// CSV counterpart of parse_meet_file. A CSV holds a single table, so the event
+// comes from an "Event" column when present, otherwise from the filename part
+// after the age range (e.g. CAN-MBSK_2024_SCY_Men_00-12_50 Free.csv).
+pub fn parse_meet_csv(
+    file_path: &Path,
+    config: &Config,
+    warnings: &mut Vec<Warning>,
+) -> Result<Vec<MeetResult>, AnalysisError> {
+    let info = parse_meet_filename(file_path, config)?;
+
+    let reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_path(file_path)
+        .map_err(|source| AnalysisError::Csv { path: file_path.to_path_buf(), source })?;
+
+    // Wrap fields as cells so the xlsx column and time handling applies
+    // unchanged. Records are converted as they're read, so a big export is
+    // never held whole; a read error ends the rows and fails the file below.
+    let mut read_error = None;
+    let mut rows = reader.into_records()
+        .map_while(|record| match record {
+            Ok(record) => Some(record.iter()
+                .map(|field| {
+                    if field.trim().is_empty() {
+                        Data::Empty
+                    } else {
+                        Data::String(field.to_string())
+                    }
+                })
+                .collect::<Vec<Data>>()),
+            Err(source) => {
+                read_error = Some(source);
+                None
+            }
+        });
+
+    // Rows up to the first non-empty one, where the header is looked for
+    let mut lookahead: Vec<Vec<Data>> = Vec::new();
+    for row in rows.by_ref() {
+        let has_content = row.iter().any(|cell| !matches!(cell, Data::Empty));
+        lookahead.push(row);
+        if has_content {
+            break;
+        }
+    }
+
+    let columns = detect_columns(lookahead.iter().map(Vec::as_slice), 0, &info.filename, config);
+    let filename_event = info.parts.get(5)
+        .and_then(|part| config.normalize_event(part));
+
+    if columns.event.is_none() && filename_event.is_none() {
+        return Err(AnalysisError::FilenameParse {
+            filename: info.filename.clone(),
+            reason: "no Event column and no event after the age range".to_string(),
+        });
+    }
+
+    let mut results = Vec::new();
+    let mut tally = RowTally::default();
+
+    let included = |(event, is_relay): (String, bool)| -> Option<(Rc<str>, bool)> {
+        config.includes_event(&event).then(|| (event.into(), is_relay))
+    };
+    let filename_event = filename_event.and_then(included);
+
+    // Event cells repeat down the file, so each distinct one is normalized once
+    let mut cell_events: HashMap<String, Option<(Rc<str>, bool)>> = HashMap::new();
+    let event_for_row = |row: &[Data]| -> Option<(Rc<str>, bool)> {
+        match columns.event {
+            Some(idx) => match row.get(idx) {
+                Some(Data::String(s)) => {
+                    if let Some(event) = cell_events.get(s) {
+                        return event.clone();
+                    }
+                    let event = config.normalize_event(s).and_then(included);
+                    cell_events.insert(s.clone(), event.clone());
+                    event
+                }
+                _ => None,
+            },
+            None => filename_event.clone(),
+        }
+    };
+    let skipped_rows = read_result_rows(lookahead.into_iter().chain(rows), &columns, &info, config,
+                                        event_for_row, &mut tally, &mut results);
+    if let Some(source) = read_error {
+        return Err(AnalysisError::Csv { path: file_path.to_path_buf(), source });
+    }
+    record_skipped_rows(&info, None, &columns, &skipped_rows, 0, warnings);
+
+    let duplicates = find_duplicates(&mut results, 0, config.dedup);
+    log_duplicates(&info, None, duplicates, config.dedup, warnings);
+
+    tally.log(&info, warnings);
+
+    Ok(results)
+}
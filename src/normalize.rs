@@ -0,0 +1,355 @@
+use regex::Regex;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, LazyLock};
+
+use crate::config::EventSort;
+
+// Distance followed by a standalone unit: "50m", "100 y", "200 yds", "400 meters"
+static DISTANCE_UNIT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(\d+)\s*(?:m|y|yd|yds|yards?|meters?|metres?)(?:\s+|$)").unwrap()
+});
+
+// Full stroke names from data files -> 2-letter abbreviations (case-insensitive).
+// Used as the default for the config's stroke_names table.
+pub fn default_stroke_names() -> HashMap<String, String> {
+    [
+        ("Freestyle", "Fr"),
+        ("Free", "Fr"),
+        ("Butterfly", "Bu"),
+        ("Fly", "Bu"),
+        ("FL", "Bu"), // Abbreviated form from standards files
+        ("Backstroke", "Bk"),
+        ("Back", "Bk"),
+        ("Breaststroke", "Br"),
+        ("Breast", "Br"),
+        ("Individual Medley", "Me"),
+        ("I.M.", "Me"),
+        ("I.M", "Me"),
+        ("IM", "Me"),
+        ("M.E.", "Me"), // Medley (I.M.)
+        ("M.E", "Me"),
+        ("Medley", "Me"), // Medley relay
+    ]
+    .into_iter()
+    .map(|(name, abbrev)| (name.to_string(), abbrev.to_string()))
+    .collect()
+}
+
+static DEFAULT_STROKE_NAMES: LazyLock<HashMap<String, String>> = LazyLock::new(default_stroke_names);
+
+// Replaces stroke names in one left-to-right pass, trying the longest name
+// first at each position so "Butterfly" wins over "Fly". A name only matches
+// when it isn't part of a longer word ("IM" in "Swim" is left alone).
+fn replace_stroke_names(event: &str, strokes: &HashMap<String, String>) -> String {
+    let mut names: Vec<(String, &str)> = strokes.iter()
+        .map(|(name, abbrev)| (name.to_ascii_lowercase(), abbrev.as_str()))
+        .filter(|(name, _)| !name.is_empty())
+        .collect();
+    names.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then_with(|| a.0.cmp(&b.0)));
+    
+    // ASCII lowercasing keeps byte offsets identical to `event`
+    let lower = event.to_ascii_lowercase();
+    let mut out = String::with_capacity(event.len());
+    let mut prev_is_letter = false;
+    let mut i = 0;
+    
+    while let Some(c) = event[i..].chars().next() {
+        if !prev_is_letter {
+            let matched = names.iter().find(|(name, _)| {
+                lower[i..].starts_with(name.as_str())
+                    && !event[i + name.len()..].chars().next().is_some_and(char::is_alphabetic)
+            });
+            if let Some((name, abbrev)) = matched {
+                out.push_str(abbrev);
+                i += name.len();
+                prev_is_letter = name.chars().last().is_some_and(char::is_alphabetic);
+                continue;
+            }
+        }
+        
+        out.push(c);
+        prev_is_letter = c.is_alphabetic();
+        i += c.len_utf8();
+    }
+    
+    out
+}
+
+pub fn normalize_event_name(event: &str) -> Option<String> {
+    normalize_event_name_with(event, &DEFAULT_STROKE_NAMES)
+}
+
+// normalize_event_name with a custom stroke name table
+pub fn normalize_event_name_with(event: &str, strokes: &HashMap<String, String>) -> Option<String> {
+    if event.trim().is_empty() {
+        return None;
+    }
+
+    // Drop the distance unit only, keeping the number ("50m Free" -> "50 Free")
+    let normalized = DISTANCE_UNIT.replace_all(event.trim(), "$1 ").into_owned();
+    
+    let normalized = replace_stroke_names(&normalized, strokes);
+    
+    // Remove ALL spaces
+    Some(normalized.replace(' ', ""))
+}
+
+// Relay sheets: "4x50 Free", "4x100m Medley Relay"
+static RELAY_EVENT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(\d+)\s*x\s*(\d+)\s*(?:(?:m|y|yds?|yards?|meters?|metres?)\b)?(.*)$").unwrap()
+});
+
+// Relays named by their total distance: "200 Free Relay", "400m Medley Relay"
+static TOTAL_DISTANCE_RELAY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(\d+)\s*(?:(?:m|y|yds?|yards?|meters?|metres?)\b)?(.*\brelay\b.*)$").unwrap()
+});
+
+static RELAY_WORD: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)\brelay\b").unwrap());
+
+// Legs of a relay named by its total distance ("200 Free Relay" is a 4x50)
+pub const RELAY_LEGS: u32 = 4;
+
+// Relay events normalize to legs x distance + stroke + "R" ("4x50 Free" -> "4x50FrR")
+// so they never collide with an individual event. A total distance followed by
+// "Relay" is split over RELAY_LEGS legs, so "200 Free Relay" is "4x50FrR" too.
+// Returns None for non-relays.
+pub fn normalize_relay_event(event: &str) -> Option<String> {
+    normalize_relay_event_with(event, &DEFAULT_STROKE_NAMES)
+}
+
+pub fn normalize_relay_event_with(event: &str, strokes: &HashMap<String, String>) -> Option<String> {
+    let event = event.trim();
+    let (legs, leg_distance, rest) = match RELAY_EVENT.captures(event) {
+        Some(caps) => (caps[1].parse::<u32>().ok()?, caps[2].parse::<u32>().ok()?, caps.get(3)?.as_str()),
+        None => {
+            let caps = TOTAL_DISTANCE_RELAY.captures(event)?;
+            let total: u32 = caps[1].parse().ok()?;
+            if total == 0 || !total.is_multiple_of(RELAY_LEGS) {
+                return None;
+            }
+            (RELAY_LEGS, total / RELAY_LEGS, caps.get(2)?.as_str())
+        }
+    };
+    
+    let stroke_name = RELAY_WORD.replace_all(rest, "");
+    let stroke = normalize_event_name_with(&stroke_name, strokes)?;
+    
+    Some(format!("{}x{}{}R", legs, leg_distance, stroke))
+}
+
+// Distance prefix of a normalized event: "50", "4x50"
+static EVENT_DISTANCE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(?:\d+x)?\d+").unwrap());
+
+// Whether a normalized name is a distance followed by a stroke abbreviation
+// ("50Fr", "50BuLap", "4x50FrR") rather than something like "Summary"
+pub fn is_event_name(event: &str, strokes: &HashMap<String, String>) -> bool {
+    let rest = match EVENT_DISTANCE.find(event) {
+        Some(distance) => &event[distance.end()..],
+        None => return false,
+    };
+    strokes.values().any(|abbrev| !abbrev.is_empty() && rest.starts_with(abbrev.as_str()))
+}
+
+// Normalized event split for sorting: "4x50FrR" -> legs 4, distance 50,
+// stroke "Fr", relay
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventParts {
+    pub legs: u32,
+    pub distance: u32,
+    pub stroke: String,
+    pub is_relay: bool,
+}
+
+static EVENT_PARTS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(?:(\d+)x)?(\d+)(.+)$").unwrap());
+
+pub fn event_parts(event: &str) -> Option<EventParts> {
+    let caps = EVENT_PARTS.captures(event)?;
+    let is_relay = caps.get(1).is_some();
+    let legs = match caps.get(1) {
+        Some(legs) => legs.as_str().parse().ok()?,
+        None => 1,
+    };
+    let distance = caps[2].parse().ok()?;
+    let stroke = &caps[3];
+    let stroke = if is_relay { stroke.strip_suffix('R').unwrap_or(stroke) } else { stroke };
+    Some(EventParts { legs, distance, stroke: stroke.to_string(), is_relay })
+}
+
+// An individual event's distance and stroke code: "200Me" -> 200, "Me"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventComponents {
+    pub distance: u32,
+    pub stroke: String,
+}
+
+// Splits a normalized individual event. None for relays ("4x50Fr", "4x50FrR")
+// and for names without a distance followed by a stroke made of letters.
+pub fn parse_event_components(normalized: &str) -> Option<EventComponents> {
+    let parts = event_parts(normalized)?;
+    if parts.is_relay || !parts.stroke.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    Some(EventComponents { distance: parts.distance, stroke: parts.stroke })
+}
+
+// Distance of the splits read from a meet file's split column
+pub const SPLIT_DISTANCE: u32 = 50;
+
+// Event a split of `event` is measured against: the SPLIT_DISTANCE of the same
+// stroke ("100Fr" -> "50Fr"). None for relays, IM (the first leg is fly) and
+// events no longer than a split.
+pub fn split_event(event: &str) -> Option<String> {
+    let event = parse_event_components(event)?;
+    if event.stroke == "Me" || event.distance <= SPLIT_DISTANCE {
+        return None;
+    }
+    Some(format!("{}{}", SPLIT_DISTANCE, event.stroke))
+}
+
+// Usual meet program order
+const STROKE_ORDER: &[&str] = &["Fr", "Bk", "Br", "Bu", "Me"];
+
+// Position of a stroke abbreviation in program order; other strokes share the
+// last place
+pub fn stroke_rank(stroke: &str) -> usize {
+    STROKE_ORDER.iter().position(|s| *s == stroke).unwrap_or(STROKE_ORDER.len())
+}
+
+// Reorders normalized events for the output rows. stroke-distance groups
+// individual events by stroke (free, back, breast, fly, IM, then any other
+// stroke alphabetically) and distance, followed by the relays in the same
+// order; names that don't parse go last.
+pub fn sort_events(events: &mut [String], sort: EventSort) {
+    match sort {
+        EventSort::Standards => {}
+        EventSort::Alpha => events.sort_by_key(|event| event.to_ascii_lowercase()),
+        EventSort::StrokeDistance => events.sort_by_cached_key(|event| match event_parts(event) {
+            Some(parts) => {
+                let rank = stroke_rank(&parts.stroke);
+                (0, parts.is_relay, rank, parts.stroke, parts.legs * parts.distance, event.clone())
+            }
+            None => (1, false, 0, String::new(), 0, event.clone()),
+        }),
+    }
+}
+
+// Normalizes a sheet or standards row name, flagging relays
+pub fn normalize_any_event(name: &str) -> Option<(String, bool)> {
+    normalize_any_event_with(name, &DEFAULT_STROKE_NAMES)
+}
+
+pub fn normalize_any_event_with(name: &str, strokes: &HashMap<String, String>) -> Option<(String, bool)> {
+    match normalize_relay_event_with(name, strokes) {
+        Some(event) => Some((event, true)),
+        None => normalize_event_name_with(name, strokes).map(|event| (event, false)),
+    }
+}
+
+// A caller-supplied replacement for the built-in meet event normalization, for
+// sheet names the stroke_names table can't handle. It gets each meet sheet
+// name (and Event cell or filename event of a CSV) and returns the event as
+// the standards name it ("50Fr", "4x50FrR"), or None to skip the sheet.
+// Returned names are checked and filtered like built-in ones; relays are
+// recognized by their "4x50" prefix. Call normalize_any_event_with from the
+// closure to fall back to the built-in rules for names it doesn't know.
+#[derive(Clone)]
+pub struct EventNormalizer(Arc<NormalizeFn>);
+
+type NormalizeFn = dyn Fn(&str) -> Option<String> + Send + Sync;
+
+impl EventNormalizer {
+    pub fn new(normalize: impl Fn(&str) -> Option<String> + Send + Sync + 'static) -> Self {
+        EventNormalizer(Arc::new(normalize))
+    }
+
+    // Same shape as normalize_any_event: the event and whether it's a relay
+    pub fn normalize(&self, name: &str) -> Option<(String, bool)> {
+        let event = (self.0)(name)?;
+        let is_relay = event_parts(&event).is_some_and(|parts| parts.is_relay);
+        Some((event, is_relay))
+    }
+}
+
+impl fmt::Debug for EventNormalizer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("EventNormalizer(..)")
+    }
+}
+
+static WHITESPACE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s+").unwrap());
+
+// Generational suffixes kept at the end of a canonical name
+const NAME_SUFFIXES: &[&str] = &["JR", "SR", "II", "III", "IV"];
+
+fn is_name_suffix(token: &str) -> bool {
+    NAME_SUFFIXES.contains(&token.trim_end_matches('.').to_ascii_uppercase().as_str())
+}
+
+// "A" or "A." after the first given name
+fn is_middle_initial(token: &str) -> bool {
+    let letters = token.trim_end_matches('.');
+    letters.chars().count() == 1 && letters.chars().all(char::is_alphabetic)
+}
+
+// "First Last Suffix" from given and family name tokens, without middle initials
+fn canonical_name(given: &[&str], family: &[&str]) -> String {
+    let mut parts: Vec<&str> = Vec::new();
+    let mut suffixes: Vec<&str> = Vec::new();
+    
+    for (i, &token) in given.iter().enumerate() {
+        if is_name_suffix(token) {
+            suffixes.push(token.trim_end_matches('.'));
+        } else if i == 0 || !is_middle_initial(token) {
+            parts.push(token);
+        }
+    }
+    for &token in family {
+        if is_name_suffix(token) {
+            suffixes.push(token.trim_end_matches('.'));
+        } else {
+            parts.push(token);
+        }
+    }
+    
+    parts.extend(suffixes);
+    parts.join(" ")
+}
+
+// Canonical athlete name used as the dedup key: trimmed with single spaces, and
+// with `reorder_last_first`, "Smith, John A." and "John Smith" both become
+// "John Smith" ("Smith Jr., John" -> "John Smith Jr")
+pub fn normalize_name(name: &str, reorder_last_first: bool) -> String {
+    let name = WHITESPACE.replace_all(name.trim(), " ");
+    
+    if !reorder_last_first {
+        return name.to_string();
+    }
+    
+    // Meet manager exports: "Last, First Middle"
+    if let Some((last, first)) = name.split_once(',') {
+        let family: Vec<&str> = last.split_whitespace().collect();
+        let given: Vec<&str> = first.split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|t| !t.is_empty())
+            .collect();
+        if family.is_empty() || given.is_empty() {
+            return name.to_string();
+        }
+        return canonical_name(&given, &family);
+    }
+    
+    // "First Middle Last Suffix"
+    let mut tokens: Vec<&str> = name.split_whitespace().collect();
+    let mut suffixes: Vec<&str> = Vec::new();
+    while tokens.len() > 1 && tokens.last().is_some_and(|t| is_name_suffix(t)) {
+        suffixes.insert(0, tokens.pop().unwrap_or_default());
+    }
+    match tokens.pop() {
+        Some(last) => {
+            let mut family = vec![last];
+            family.extend(suffixes);
+            canonical_name(&tokens, &family)
+        }
+        None => name.to_string(),
+    }
+}
@@ -0,0 +1,47 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::error::AnalysisError;
+
+// "out/qualifier_counts.xlsx" -> "out/qualifier_counts.xlsx.tmp"
+pub fn temp_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".tmp");
+    PathBuf::from(name)
+}
+
+// Runs `write` against a temp file next to `path` and renames it over `path`
+// only when it succeeds, so an error or Ctrl-C mid-write never leaves a
+// truncated output behind. A failed write removes the temp file.
+pub fn write_atomic<F>(path: &Path, write: F) -> Result<(), AnalysisError>
+where
+    F: FnOnce(&Path) -> Result<(), AnalysisError>,
+{
+    let temp = temp_path(path);
+    if let Err(e) = write(&temp) {
+        let _ = std::fs::remove_file(&temp);
+        return Err(e);
+    }
+
+    replace_file(&temp, path).map_err(|source| AnalysisError::OutputReplace {
+        path: path.to_path_buf(),
+        temp: temp.clone(),
+        source,
+    })
+}
+
+// rename() replaces an existing file on Unix. Windows can refuse when the
+// target exists (or is still being released by another program), so there the
+// old file is removed and the rename retried once.
+fn replace_file(from: &Path, to: &Path) -> io::Result<()> {
+    match std::fs::rename(from, to) {
+        Ok(()) => Ok(()),
+        #[cfg(windows)]
+        Err(e) if to.exists() => {
+            log::debug!("Rename over {} failed ({}); removing it and retrying", to.display(), e);
+            std::fs::remove_file(to)?;
+            std::fs::rename(from, to)
+        }
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,1182 @@
+use log::debug;
+use rust_xlsxwriter::{ConditionalFormat2ColorScale, Format, Workbook, Worksheet};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::age::age_sort_key;
+use crate::config::{Config, CountMode, EventSort, Layout};
+use crate::count::{
+    AgeMismatch, BestVsCut, MultiEventQualifier, Participation, Ranking, RosterEntry, StandardComparison,
+    StatusChange, UnmatchedKey,
+};
+use crate::error::AnalysisError;
+use crate::history::{CountHistory, HISTORY_KEY_COLUMNS, HISTORY_SHEET};
+use crate::normalize::{event_parts, sort_events, stroke_rank};
+use crate::output::write_atomic;
+use crate::standards::{cut_level_order, CutLevelKey, EventStandards, StandardKey};
+use crate::time::format_time;
+
+// Qualifier counts for every gender, in output order. This is the structure
+// serialized by --format json, so field names are part of the documented schema.
+#[derive(Debug, Serialize)]
+pub struct QualifierReport {
+    pub genders: Vec<GenderReport>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GenderReport {
+    pub gender: String,
+    // Meet course these counts cover, only set when the results mix courses
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub course: Option<String>,
+    pub age_groups: Vec<String>,
+    // Column labels, parallel to age_groups: the standards header ("12&U"),
+    // or "13-14" for a range-fed group
+    pub age_labels: Vec<String>,
+    // Individual events; relays are kept apart in `relays` so the totals of
+    // one never include the other
+    pub events: Vec<EventCounts>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub relays: Vec<EventCounts>,
+    // `events` and `relays` summed per stroke over every distance; see stroke_totals
+    pub stroke_totals: Vec<EventCounts>,
+    pub summary: Vec<AgeGroupSummary>,
+    // Only when the standards have more than one cut level (A/AA/AAA)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cut_levels: Vec<CutLevelCounts>,
+}
+
+// Count matrix of swims/athletes whose fastest cut made was this level
+#[derive(Debug, Serialize)]
+pub struct CutLevelCounts {
+    pub cut_level: String,
+    pub events: Vec<EventCounts>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub relays: Vec<EventCounts>,
+}
+
+// One row of the count matrix
+#[derive(Debug, Serialize)]
+pub struct EventCounts {
+    pub event: String,
+    pub counts: Vec<AgeGroupCount>,
+    // Swum at the meets but missing from the standards (--events-from-meets);
+    // its counts are all 0 and the grids say "no standard" instead
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub no_standard: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AgeGroupCount {
+    pub age_group: String,
+    pub qualifying_swims: usize,
+    pub qualified_athletes: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AgeGroupSummary {
+    pub age_group: String,
+    pub total_athletes: usize,
+    pub unique_qualifiers: usize,
+    // unique_qualifiers as a percent of total_athletes; None with no athletes
+    pub qualified_pct: Option<f64>,
+}
+
+// Gathers the counts into per-gender matrices: events in standards order with
+// the relays split off into their own matrix, age groups sorted numerically
+#[allow(clippy::too_many_arguments)]
+pub fn build_report(
+    standards: &HashMap<String, EventStandards>,
+    event_orders: &HashMap<String, Vec<String>>,
+    qualifier_counts: &HashMap<StandardKey, usize>,
+    athlete_counts: &HashMap<StandardKey, usize>,
+    level_counts: &HashMap<CutLevelKey, usize>,
+    level_athlete_counts: &HashMap<CutLevelKey, usize>,
+    unique_qualifiers: &HashMap<(String, String), HashSet<String>>,
+    total_athletes: &HashMap<(String, String), HashSet<String>>,
+    age_labels: &HashMap<(String, String), String>,
+) -> QualifierReport {
+    let mut genders = Vec::new();
+    
+    // A single cut level is already the main matrix
+    let levels = cut_level_order(standards);
+    let levels: &[String] = if levels.len() > 1 { &levels } else { &[] };
+
+    for gender in &gender_order(standards) {
+        // Get standards and event order for this gender
+        let gender_standards = match standards.get(gender) {
+            Some(s) => s,
+            None => continue,
+        };
+
+        let event_order = match event_orders.get(gender) {
+            Some(o) => o,
+            None => continue,
+        };
+
+        // Collect all age groups
+        let mut age_groups: HashSet<String> = HashSet::new();
+        for event_standards in gender_standards.values() {
+            for age in event_standards.keys() {
+                age_groups.insert(age.clone());
+            }
+        }
+
+        let mut age_groups_vec: Vec<String> = age_groups.into_iter().collect();
+        age_groups_vec.sort_by_key(|a| age_sort_key(a));
+
+        let (relay_order, individual_order): (Vec<String>, Vec<String>) = event_order.iter()
+            .cloned()
+            .partition(|event| event_parts(event).is_some_and(|parts| parts.is_relay));
+
+        let counts = |age: &str, event: &str| {
+            let key = (gender.to_string(), age.to_string(), event.to_string());
+            (
+                qualifier_counts.get(&key).copied().unwrap_or(0),
+                athlete_counts.get(&key).copied().unwrap_or(0),
+            )
+        };
+        let events = event_matrix(&individual_order, &age_groups_vec, gender_standards, counts);
+        let relays = event_matrix(&relay_order, &age_groups_vec, gender_standards, counts);
+
+        let cut_levels = levels.iter()
+            .map(|level| {
+                let counts = |age: &str, event: &str| {
+                    let key = (gender.to_string(), age.to_string(), event.to_string(), level.clone());
+                    (
+                        level_counts.get(&key).copied().unwrap_or(0),
+                        level_athlete_counts.get(&key).copied().unwrap_or(0),
+                    )
+                };
+                CutLevelCounts {
+                    cut_level: level.clone(),
+                    events: event_matrix(&individual_order, &age_groups_vec, gender_standards, counts),
+                    relays: event_matrix(&relay_order, &age_groups_vec, gender_standards, counts),
+                }
+            })
+            .collect();
+
+        let summary = age_groups_vec.iter()
+            .map(|age| {
+                let key = (gender.to_string(), age.clone());
+                let total = total_athletes.get(&key).map(|s| s.len()).unwrap_or(0);
+                let qualified = unique_qualifiers.get(&key).map(|s| s.len()).unwrap_or(0);
+                AgeGroupSummary {
+                    age_group: age.clone(),
+                    total_athletes: total,
+                    unique_qualifiers: qualified,
+                    qualified_pct: if total > 0 {
+                        Some(qualified as f64 / total as f64 * 100.0)
+                    } else {
+                        None
+                    },
+                }
+            })
+            .collect();
+
+        let labels = age_groups_vec.iter()
+            .map(|age| {
+                age_labels.get(&(gender.to_string(), age.clone()))
+                    .cloned()
+                    .unwrap_or_else(|| age.clone())
+            })
+            .collect();
+
+        let mut totals = stroke_totals(&events);
+        totals.extend(stroke_totals(&relays));
+
+        genders.push(GenderReport {
+            gender: gender.to_string(),
+            course: None,
+            age_groups: age_groups_vec,
+            age_labels: labels,
+            events,
+            relays,
+            stroke_totals: totals,
+            summary,
+            cut_levels,
+        });
+    }
+
+    QualifierReport { genders }
+}
+
+// One EventCounts row per event; `counts` gives (swims, athletes) for an age/event.
+// Events the gender's standards don't have are flagged no_standard.
+fn event_matrix(
+    event_order: &[String],
+    age_groups: &[String],
+    gender_standards: &EventStandards,
+    counts: impl Fn(&str, &str) -> (usize, usize),
+) -> Vec<EventCounts> {
+    event_order.iter()
+        .map(|event| EventCounts {
+            event: event.clone(),
+            counts: age_groups.iter()
+                .map(|age| {
+                    let (qualifying_swims, qualified_athletes) = counts(age, event);
+                    AgeGroupCount {
+                        age_group: age.clone(),
+                        qualifying_swims,
+                        qualified_athletes,
+                    }
+                })
+                .collect(),
+            no_standard: !gender_standards.contains_key(event),
+        })
+        .collect()
+}
+
+// --events-from-meets: each gender's event order followed by the events its
+// unmatched results were swum in that have no standard at all, in stroke and
+// distance order. Events with a standard but no age group for the swimmer's
+// age are already in the order.
+pub fn with_meet_events(
+    event_orders: &HashMap<String, Vec<String>>,
+    standards: &HashMap<String, EventStandards>,
+    unmatched: &[UnmatchedKey],
+) -> HashMap<String, Vec<String>> {
+    let mut orders = event_orders.clone();
+    for (gender, order) in &mut orders {
+        let gender_standards = match standards.get(gender) {
+            Some(s) => s,
+            None => continue,
+        };
+        // unmatched is sorted by sex then event, so repeats are adjacent
+        let mut extra: Vec<String> = unmatched.iter()
+            .filter(|key| &key.sex == gender && !gender_standards.contains_key(&key.event))
+            .map(|key| key.event.clone())
+            .filter(|event| !order.contains(event))
+            .collect();
+        extra.dedup();
+        if !extra.is_empty() {
+            debug!("{}: {} events swum without a standard: {:?}", gender, extra.len(), extra);
+        }
+        sort_events(&mut extra, EventSort::StrokeDistance);
+        order.extend(extra);
+    }
+    orders
+}
+
+// Label of the combined column added by combine_ages
+pub const ALL_AGES: &str = "All Ages";
+
+// Whether a grid's last column is the combined one; it takes the place of the
+// per-event "Total" column, which would only repeat it
+fn has_all_ages(age_labels: &[String]) -> bool {
+    age_labels.last().is_some_and(|label| label == ALL_AGES)
+}
+
+fn combined_count(counts: &[AgeGroupCount]) -> AgeGroupCount {
+    AgeGroupCount {
+        age_group: ALL_AGES.to_string(),
+        qualifying_swims: counts.iter().map(|c| c.qualifying_swims).sum(),
+        qualified_athletes: counts.iter().map(|c| c.qualified_athletes).sum(),
+    }
+}
+
+fn combine_rows(events: &mut [EventCounts], keep_ages: bool) {
+    for event in events {
+        let all_ages = combined_count(&event.counts);
+        if !keep_ages {
+            event.counts.clear();
+        }
+        event.counts.push(all_ages);
+    }
+}
+
+// --combine-ages: adds an "All Ages" column to every grid summing each event
+// over its age groups, for clubs whose per-age counts are sparse. Without
+// `keep_ages` (--combine-ages-only) it replaces the age group columns. The
+// summary's athlete totals are summed over the age groups too, so an athlete
+// matched to two age groups counts in both.
+pub fn combine_ages(report: &mut QualifierReport, keep_ages: bool) {
+    for gender_report in &mut report.genders {
+        combine_rows(&mut gender_report.events, keep_ages);
+        combine_rows(&mut gender_report.relays, keep_ages);
+        combine_rows(&mut gender_report.stroke_totals, keep_ages);
+        for level in &mut gender_report.cut_levels {
+            combine_rows(&mut level.events, keep_ages);
+            combine_rows(&mut level.relays, keep_ages);
+        }
+
+        let total_athletes: usize = gender_report.summary.iter().map(|s| s.total_athletes).sum();
+        let unique_qualifiers: usize = gender_report.summary.iter().map(|s| s.unique_qualifiers).sum();
+        let all_ages = AgeGroupSummary {
+            age_group: ALL_AGES.to_string(),
+            total_athletes,
+            unique_qualifiers,
+            qualified_pct: if total_athletes > 0 {
+                Some(unique_qualifiers as f64 / total_athletes as f64 * 100.0)
+            } else {
+                None
+            },
+        };
+        if !keep_ages {
+            gender_report.summary.clear();
+            gender_report.age_groups.clear();
+            gender_report.age_labels.clear();
+        }
+        gender_report.summary.push(all_ages);
+        gender_report.age_groups.push(ALL_AGES.to_string());
+        gender_report.age_labels.push(ALL_AGES.to_string());
+    }
+}
+
+// One count in the long layout: a metric for a gender and age group, plus the
+// event and cut level it belongs to (None for the per-age summary metrics and
+// the main counts respectively)
+#[derive(Debug, Clone, PartialEq)]
+pub struct LongRow {
+    pub gender: String,
+    pub course: Option<String>,
+    pub age_group: String, // Column label, as in the wide grids
+    pub event: Option<String>,
+    pub cut_level: Option<String>,
+    pub metric: &'static str,
+    pub value: f64,
+}
+
+// The report flattened for --layout long: per gender, qualifying swims and
+// qualified athletes for each event and age group, the relays under their own
+// metric names, then the summary metrics per age group, then the counts per
+// cut level. Stroke totals are left out since they're sums of the event rows,
+// and so are standard-less events, which have nothing to count.
+// "Qualified %" is 0-100 and missing for age groups with no athletes.
+pub fn long_rows(report: &QualifierReport) -> Vec<LongRow> {
+    let mut rows = Vec::new();
+    
+    for gender_report in &report.genders {
+        push_event_rows(&mut rows, gender_report, &gender_report.events, None, INDIVIDUAL_METRICS);
+        push_event_rows(&mut rows, gender_report, &gender_report.relays, None, RELAY_METRICS);
+        
+        for (age, summary) in gender_report.summary.iter().enumerate() {
+            let summary_row = |metric, value| LongRow::new(gender_report, age, None, None, metric, value);
+            rows.push(summary_row("Total Unique Athletes", summary.total_athletes as f64));
+            rows.push(summary_row("Unique Qualifiers", summary.unique_qualifiers as f64));
+            if let Some(pct) = summary.qualified_pct {
+                rows.push(summary_row("Qualified %", pct));
+            }
+        }
+        
+        for level in &gender_report.cut_levels {
+            let cut_level = Some(level.cut_level.as_str());
+            push_event_rows(&mut rows, gender_report, &level.events, cut_level, INDIVIDUAL_METRICS);
+            push_event_rows(&mut rows, gender_report, &level.relays, cut_level, RELAY_METRICS);
+        }
+    }
+    
+    rows
+}
+
+impl LongRow {
+    fn new(
+        gender_report: &GenderReport,
+        age: usize,
+        event: Option<&str>,
+        cut_level: Option<&str>,
+        metric: &'static str,
+        value: f64,
+    ) -> Self {
+        LongRow {
+            gender: gender_report.gender.clone(),
+            course: gender_report.course.clone(),
+            age_group: gender_report.age_labels[age].clone(),
+            event: event.map(str::to_string),
+            cut_level: cut_level.map(str::to_string),
+            metric,
+            value,
+        }
+    }
+}
+
+// Metric names for (qualifying_swims, qualified_athletes). A relay row is one
+// team, so summing a metric never mixes relays with individual swims.
+const INDIVIDUAL_METRICS: (&str, &str) = ("Qualifying Swims", "Qualified Athletes");
+const RELAY_METRICS: (&str, &str) = ("Qualifying Relays", "Qualified Relay Teams");
+
+// Both counts for every event/age of one matrix, named by `metrics`
+fn push_event_rows(
+    rows: &mut Vec<LongRow>,
+    gender_report: &GenderReport,
+    events: &[EventCounts],
+    cut_level: Option<&str>,
+    metrics: (&'static str, &'static str),
+) {
+    let (swims_metric, athletes_metric) = metrics;
+    for event in events.iter().filter(|event| !event.no_standard) {
+        for (age, count) in event.counts.iter().enumerate() {
+            let event_row = |metric, value| {
+                LongRow::new(gender_report, age, Some(&event.event), cut_level, metric, value)
+            };
+            rows.push(event_row(swims_metric, count.qualifying_swims as f64));
+            rows.push(event_row(athletes_metric, count.qualified_athletes as f64));
+        }
+    }
+}
+
+// One row per stroke ("Fr", "Bk", ...) summing the event rows over every
+// distance, in program order with relays after the individual strokes ("Fr
+// Relay"). qualified_athletes is summed too, so an athlete who qualified at two
+// distances counts twice. Events whose name doesn't parse are left out.
+pub fn stroke_totals(events: &[EventCounts]) -> Vec<EventCounts> {
+    let mut totals: BTreeMap<(bool, usize, String), Vec<AgeGroupCount>> = BTreeMap::new();
+    
+    for event in events {
+        let parts = match event_parts(&event.event) {
+            Some(parts) => parts,
+            None => continue,
+        };
+        let key = (parts.is_relay, stroke_rank(&parts.stroke), parts.stroke);
+        let row = totals.entry(key).or_insert_with(|| {
+            event.counts.iter()
+                .map(|count| AgeGroupCount {
+                    age_group: count.age_group.clone(),
+                    qualifying_swims: 0,
+                    qualified_athletes: 0,
+                })
+                .collect()
+        });
+        for (total, count) in row.iter_mut().zip(&event.counts) {
+            total.qualifying_swims += count.qualifying_swims;
+            total.qualified_athletes += count.qualified_athletes;
+        }
+    }
+    
+    totals.into_iter()
+        .map(|((is_relay, _, stroke), counts)| EventCounts {
+            event: if is_relay { format!("{} Relay", stroke) } else { stroke },
+            counts,
+            no_standard: false,
+        })
+        .collect()
+}
+
+// Men and Women first as before, then any other gender tabs alphabetically
+fn gender_order(standards: &HashMap<String, EventStandards>) -> Vec<String> {
+    let mut genders: Vec<String> = standards.keys().cloned().collect();
+    genders.sort_by_key(|g| match g.as_str() {
+        "Men" => (0, String::new()),
+        "Women" => (1, String::new()),
+        _ => (2, g.clone()),
+    });
+    genders
+}
+
+// "Men" -> "Mens", "Women" -> "Womens"; other genders keep their name
+pub fn gender_label(gender: &str) -> String {
+    match gender {
+        "Men" | "Women" => format!("{}s", gender),
+        _ => gender.to_string(),
+    }
+}
+
+// The gender label, followed by the course when the report is split by
+// course ("Mens LCM")
+fn gender_sheet_name(gender_report: &GenderReport) -> String {
+    let name = gender_label(&gender_report.gender);
+    match &gender_report.course {
+        Some(course) => format!("{} {}", name, course),
+        None => name,
+    }
+}
+
+// Excel's limits for a worksheet name
+const MAX_SHEET_NAME: usize = 31;
+const ILLEGAL_SHEET_CHARS: &[char] = &['[', ']', ':', '*', '?', '/', '\\'];
+
+// A name Excel accepts for a new sheet: illegal characters dropped, no leading
+// or trailing apostrophe, at most 31 characters, and different from every name
+// in `existing` (compared case-insensitively, as Excel does). A clash gets a
+// numeric suffix, shortening the name to fit: "Mens SCY AA (2)".
+pub fn sanitize_sheet_name(name: &str, existing: &[String]) -> String {
+    let cleaned: String = name.chars().filter(|c| !ILLEGAL_SHEET_CHARS.contains(c)).collect();
+    let cleaned = cleaned.trim().trim_matches('\'').trim();
+    let cleaned = if cleaned.is_empty() { "Sheet" } else { cleaned };
+    
+    let taken = |candidate: &str| existing.iter().any(|e| e.to_lowercase() == candidate.to_lowercase());
+    let fit = |base: &str, suffix: &str| {
+        let keep = MAX_SHEET_NAME - suffix.chars().count();
+        let base: String = base.chars().take(keep).collect();
+        format!("{}{}", base.trim_end(), suffix)
+    };
+    
+    let mut candidate = fit(cleaned, "");
+    let mut n = 2;
+    while taken(&candidate) {
+        candidate = fit(cleaned, &format!(" ({})", n));
+        n += 1;
+    }
+    candidate
+}
+
+// Adds a worksheet under the sanitized form of `name`
+fn add_named_sheet<'a>(workbook: &'a mut Workbook, name: &str) -> Result<&'a mut Worksheet, AnalysisError> {
+    let existing: Vec<String> = workbook.worksheets().iter().map(|sheet| sheet.name()).collect();
+    let sheet_name = sanitize_sheet_name(name, &existing);
+    if sheet_name != name {
+        debug!("Sheet name '{}' written as '{}'", name, sheet_name);
+    }
+    let sheet = workbook.add_worksheet();
+    sheet.set_name(sheet_name)?;
+    Ok(sheet)
+}
+
+// Writes the count sheets (one per gender, or a single "Counts" sheet with
+// --layout long), then two roster sheets ("Qualifiers Detail" and "Near
+// Misses"), "Rankings", "Best Times vs Cut" (when config.best_vs_cut is set),
+// "Changed Status" (when config.previous_standards is set), "Unmatched",
+// "Age Mismatch", "Participation" and, with --append, "Count History".
+#[allow(clippy::too_many_arguments)]
+pub fn write_workbook(
+    config: &Config,
+    report: &QualifierReport,
+    qualifiers: &[StandardComparison],
+    near_misses: &[StandardComparison],
+    rankings: &[Ranking],
+    best_times: &[BestVsCut],
+    status_changes: &[StatusChange],
+    unmatched: &[UnmatchedKey],
+    age_mismatches: &[AgeMismatch],
+    multi_event: &[MultiEventQualifier],
+    participation: &[Participation],
+    history: Option<&CountHistory>,
+) -> Result<(), AnalysisError> {
+    let mut workbook = Workbook::new();
+
+    match config.layout {
+        Layout::Wide => write_gender_sheets(&mut workbook, config, report)?,
+        Layout::Long => write_long_sheet(&mut workbook, &long_rows(report))?,
+    }
+
+    write_comparison_sheet(&mut workbook, "Qualifiers Detail", qualifiers)?;
+    write_comparison_sheet(&mut workbook, "Near Misses", near_misses)?;
+    write_rankings_sheet(&mut workbook, rankings)?;
+    write_multi_event_sheet(&mut workbook, multi_event)?;
+    if config.best_vs_cut {
+        write_best_vs_cut_sheet(&mut workbook, best_times)?;
+    }
+    if config.previous_standards.is_some() {
+        write_status_changes_sheet(&mut workbook, status_changes)?;
+    }
+    write_unmatched_sheet(&mut workbook, unmatched)?;
+    write_age_mismatch_sheet(&mut workbook, age_mismatches)?;
+    write_participation_sheet(&mut workbook, participation)?;
+    if let Some(history) = history {
+        write_history_sheet(&mut workbook, history)?;
+    }
+
+    if config.autofit {
+        // Widths follow the longest value written in each column; the header
+        // row and the event/name column stay in view while scrolling
+        for sheet in workbook.worksheets_mut() {
+            sheet.autofit();
+            sheet.set_freeze_panes(1, 1)?;
+        }
+    }
+
+    write_atomic(&config.output, |temp| Ok(workbook.save(temp)?))?;
+
+    Ok(())
+}
+
+// Marks a standard-less event's row in place of its counts
+const NO_STANDARD: &str = "no standard";
+
+// Writes a header row (label, age groups, "Total"), one row per event with a
+// per-event total (or NO_STANDARD), and a "Total" row per age group with the
+// grand total in the corner. With `highlight`, the counts (not the totals) get a white-to-blue
+// color scale. Returns the row after the totals. An "All Ages" column from
+// combine_ages replaces the per-event total and isn't shaded alongside ages.
+fn write_count_matrix(
+    sheet: &mut Worksheet,
+    start_row: u32,
+    label: &str,
+    age_groups: &[String],
+    events: &[EventCounts],
+    highlight: bool,
+    value: impl Fn(&AgeGroupCount) -> usize,
+) -> Result<u32, AnalysisError> {
+    let all_ages = has_all_ages(age_groups);
+    let total_col = (age_groups.len() + 1) as u16;
+    // Per-age columns; the color scale covers these only
+    let age_cols = if all_ages && age_groups.len() > 1 { age_groups.len() - 1 } else { age_groups.len() };
+
+    sheet.write_string(start_row, 0, label)?;
+    for (i, age) in age_groups.iter().enumerate() {
+        sheet.write_string(start_row, (i + 1) as u16, age)?;
+    }
+    if !all_ages {
+        sheet.write_string(start_row, total_col, "Total")?;
+    }
+
+    // Write data rows following event order
+    let mut column_totals = vec![0usize; age_groups.len()];
+    let mut row = start_row + 1;
+    for event in events {
+        sheet.write_string(row, 0, &event.event)?;
+        if event.no_standard {
+            sheet.write_string(row, 1, NO_STANDARD)?;
+            row += 1;
+            continue;
+        }
+
+        let mut event_total = 0;
+        for (col, count) in event.counts.iter().enumerate() {
+            let n = value(count);
+            sheet.write_number(row, (col + 1) as u16, n as f64)?;
+            column_totals[col] += n;
+            event_total += n;
+        }
+        if !all_ages {
+            sheet.write_number(row, total_col, event_total as f64)?;
+        }
+
+        row += 1;
+    }
+
+    if highlight && row > start_row + 1 && !age_groups.is_empty() {
+        let scale = ConditionalFormat2ColorScale::new()
+            .set_minimum_color(0xFFFFFF)
+            .set_maximum_color(0x4F81BD);
+        sheet.add_conditional_format(start_row + 1, 1, row - 1, age_cols as u16, &scale)?;
+    }
+
+    sheet.write_string(row, 0, "Total")?;
+    for (col, total) in column_totals.iter().enumerate() {
+        sheet.write_number(row, (col + 1) as u16, *total as f64)?;
+    }
+    if !all_ages {
+        sheet.write_number(row, total_col, column_totals.iter().sum::<usize>() as f64)?;
+    }
+
+    Ok(row + 1)
+}
+
+// What a count grid shows: its title for events and relays, and the count
+struct GridMetric {
+    events: &'static str,
+    relays: &'static str,
+    value: fn(&AgeGroupCount) -> usize,
+}
+
+impl GridMetric {
+    // "Event (Qualifying Swims)": a grid label naming the metric
+    fn label(&self, label: &str) -> String {
+        format!("{} ({})", label, self.events)
+    }
+
+    fn relay_label(&self, label: &str) -> String {
+        format!("{} ({})", label, self.relays)
+    }
+}
+
+const SWIMS_GRID: GridMetric = GridMetric {
+    events: "Qualifying Swims",
+    relays: "Qualifying Relays",
+    value: |c: &AgeGroupCount| c.qualifying_swims,
+};
+
+const ATHLETES_GRID: GridMetric = GridMetric {
+    events: "Qualified Athletes",
+    relays: "Qualified Relay Teams",
+    value: |c: &AgeGroupCount| c.qualified_athletes,
+};
+
+// (main grid, second grid) for --count-mode
+fn grid_metrics(count_mode: CountMode) -> (GridMetric, GridMetric) {
+    match count_mode {
+        CountMode::Swims => (SWIMS_GRID, ATHLETES_GRID),
+        CountMode::Athletes => (ATHLETES_GRID, SWIMS_GRID),
+    }
+}
+
+// --layout wide: one sheet per gender with the --count-mode metric per
+// event/age (and per relay in a separate "Relay" grid), the unique athlete
+// summary rows, the same metric per stroke, then the other metric per
+// event/age and per relay; plus one sheet per cut level when there are several
+fn write_gender_sheets(
+    workbook: &mut Workbook,
+    config: &Config,
+    report: &QualifierReport,
+) -> Result<(), AnalysisError> {
+    let percent_format = Format::new().set_num_format("0.0%");
+
+    for gender_report in &report.genders {
+        let sheet = add_named_sheet(workbook, &gender_sheet_name(gender_report))?;
+
+        // The first grid shows the --count-mode metric, named in its header
+        let (main, other) = grid_metrics(config.count_mode);
+        let mut row = write_count_matrix(sheet, 0, &main.label("Event"), &gender_report.age_labels,
+                                         &gender_report.events, config.highlight, main.value)?;
+        if !gender_report.relays.is_empty() {
+            row = write_count_matrix(sheet, row + 1, &main.relay_label("Relay"), &gender_report.age_labels,
+                                     &gender_report.relays, config.highlight, main.value)?;
+        }
+
+        // Add summary rows
+        row += 1;
+        sheet.write_string(row, 0, "Total Unique Athletes")?;
+        for (col, summary) in gender_report.summary.iter().enumerate() {
+            sheet.write_number(row, (col + 1) as u16, summary.total_athletes as f64)?;
+        }
+
+        row += 1;
+        sheet.write_string(row, 0, "Unique Qualifiers")?;
+        for (col, summary) in gender_report.summary.iter().enumerate() {
+            sheet.write_number(row, (col + 1) as u16, summary.unique_qualifiers as f64)?;
+        }
+
+        // Left blank for age groups without athletes
+        row += 1;
+        sheet.write_string(row, 0, "Qualified %")?;
+        for (col, summary) in gender_report.summary.iter().enumerate() {
+            if let Some(pct) = summary.qualified_pct {
+                sheet.write_number_with_format(row, (col + 1) as u16, pct / 100.0, &percent_format)?;
+            }
+        }
+
+        // The main metric per stroke, all distances together
+        row += 2;
+        row = write_count_matrix(sheet, row, &main.label("Stroke Totals"), &gender_report.age_labels,
+                                 &gender_report.stroke_totals, config.highlight, main.value)?;
+
+        // Same grid with the other metric
+        row += 1;
+        row = write_count_matrix(sheet, row, other.events, &gender_report.age_labels,
+                                 &gender_report.events, config.highlight, other.value)?;
+        if !gender_report.relays.is_empty() {
+            write_count_matrix(sheet, row + 1, other.relays, &gender_report.age_labels,
+                               &gender_report.relays, config.highlight, other.value)?;
+        }
+
+        // One sheet per cut level, e.g. "Mens AA", laid out like the grids above
+        for level in &gender_report.cut_levels {
+            let sheet_name = format!("{} {}", gender_sheet_name(gender_report), level.cut_level);
+            let sheet = add_named_sheet(workbook, &sheet_name)?;
+
+            let mut row = write_count_matrix(sheet, 0, &main.label("Event"), &gender_report.age_labels,
+                                             &level.events, config.highlight, main.value)?;
+            if !level.relays.is_empty() {
+                let label = main.relay_label("Relay");
+                row = write_count_matrix(sheet, row + 1, &label, &gender_report.age_labels, &level.relays,
+                                         config.highlight, main.value)?;
+            }
+            row = write_count_matrix(sheet, row + 1, other.events, &gender_report.age_labels,
+                                     &level.events, config.highlight, other.value)?;
+            if !level.relays.is_empty() {
+                write_count_matrix(sheet, row + 1, other.relays, &gender_report.age_labels,
+                                   &level.relays, config.highlight, other.value)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// --layout long: every count on one "Counts" sheet, one row each
+fn write_long_sheet(workbook: &mut Workbook, rows: &[LongRow]) -> Result<(), AnalysisError> {
+    let sheet = add_named_sheet(workbook, "Counts")?;
+
+    let headers = ["Gender", "Course", "Age Group", "Event", "Cut Level", "Metric", "Value"];
+    for (col, header) in headers.iter().enumerate() {
+        sheet.write_string(0, col as u16, *header)?;
+    }
+
+    for (i, entry) in rows.iter().enumerate() {
+        let row = (i + 1) as u32;
+        sheet.write_string(row, 0, &entry.gender)?;
+        if let Some(course) = &entry.course {
+            sheet.write_string(row, 1, course)?;
+        }
+        sheet.write_string(row, 2, &entry.age_group)?;
+        if let Some(event) = &entry.event {
+            sheet.write_string(row, 3, event)?;
+        }
+        if let Some(cut_level) = &entry.cut_level {
+            sheet.write_string(row, 4, cut_level)?;
+        }
+        sheet.write_string(row, 5, entry.metric)?;
+        sheet.write_number(row, 6, entry.value)?;
+    }
+
+    Ok(())
+}
+
+// One row per athlete/event: name, sex, age group, event, time and standard
+// (as M:SS.xx text), the margin under the standard in seconds (negative on the
+// Near Misses sheet) and the time as a percent of the standard, left blank
+// without a usable standard
+fn write_comparison_sheet(
+    workbook: &mut Workbook,
+    sheet_name: &str,
+    rows: &[StandardComparison],
+) -> Result<(), AnalysisError> {
+    let seconds_format = Format::new().set_num_format("0.00");
+    let percent_format = Format::new().set_num_format("0.0%");
+
+    let sheet = add_named_sheet(workbook, sheet_name)?;
+
+    let headers = ["Name", "Sex", "Age Group", "Event", "Time", "Standard", "Margin", "% of Cut"];
+    for (col, header) in headers.iter().enumerate() {
+        sheet.write_string(0, col as u16, *header)?;
+    }
+
+    for (i, entry) in rows.iter().enumerate() {
+        let row = (i + 1) as u32;
+        sheet.write_string(row, 0, &entry.name)?;
+        sheet.write_string(row, 1, &entry.sex)?;
+        sheet.write_string(row, 2, &entry.age_group)?;
+        sheet.write_string(row, 3, &entry.event)?;
+        sheet.write_string(row, 4, format_time(entry.time))?;
+        sheet.write_string(row, 5, format_time(entry.standard))?;
+        sheet.write_number_with_format(row, 6, entry.margin(), &seconds_format)?;
+        if let Some(pct) = entry.pct_of_standard() {
+            sheet.write_number_with_format(row, 7, pct / 100.0, &percent_format)?;
+        }
+    }
+
+    Ok(())
+}
+
+// Leaderboard per sex/age/event: rank, name, best time and whether it qualified
+fn write_rankings_sheet(workbook: &mut Workbook, rankings: &[Ranking]) -> Result<(), AnalysisError> {
+    let sheet = add_named_sheet(workbook, "Rankings")?;
+
+    for (col, header) in ["Sex", "Age", "Event", "Rank", "Name", "Time", "Qualified"].iter().enumerate() {
+        sheet.write_string(0, col as u16, *header)?;
+    }
+
+    for (i, entry) in rankings.iter().enumerate() {
+        let row = (i + 1) as u32;
+        sheet.write_string(row, 0, &entry.sex)?;
+        sheet.write_string(row, 1, &entry.age)?;
+        sheet.write_string(row, 2, &entry.event)?;
+        sheet.write_number(row, 3, entry.rank as f64)?;
+        sheet.write_string(row, 4, &entry.name)?;
+        sheet.write_string(row, 5, format_time(entry.time))?;
+        sheet.write_string(row, 6, if entry.qualified { "Yes" } else { "No" })?;
+    }
+
+    Ok(())
+}
+
+// Athletes who qualified in two or more events, most events first
+fn write_multi_event_sheet(workbook: &mut Workbook, rows: &[MultiEventQualifier]) -> Result<(), AnalysisError> {
+    let sheet = add_named_sheet(workbook, "Multi-Event Qualifiers")?;
+
+    for (col, header) in ["Name", "Sex", "Age Group", "Events"].iter().enumerate() {
+        sheet.write_string(0, col as u16, *header)?;
+    }
+
+    for (i, entry) in rows.iter().enumerate() {
+        let row = (i + 1) as u32;
+        sheet.write_string(row, 0, &entry.name)?;
+        sheet.write_string(row, 1, &entry.sex)?;
+        sheet.write_string(row, 2, &entry.age_group)?;
+        sheet.write_number(row, 3, entry.events as f64)?;
+    }
+
+    Ok(())
+}
+
+// Every standards age group/event with its cut and the fastest swim against it;
+// the time columns stay blank when nobody swam the event
+fn write_best_vs_cut_sheet(workbook: &mut Workbook, rows: &[BestVsCut]) -> Result<(), AnalysisError> {
+    let seconds_format = Format::new().set_num_format("0.00");
+
+    let sheet = add_named_sheet(workbook, "Best Times vs Cut")?;
+
+    let headers = ["Sex", "Age Group", "Event", "Standard", "Best Time", "Name", "Gap"];
+    for (col, header) in headers.iter().enumerate() {
+        sheet.write_string(0, col as u16, *header)?;
+    }
+
+    for (i, entry) in rows.iter().enumerate() {
+        let row = (i + 1) as u32;
+        sheet.write_string(row, 0, &entry.sex)?;
+        sheet.write_string(row, 1, &entry.age_group)?;
+        sheet.write_string(row, 2, &entry.event)?;
+        sheet.write_string(row, 3, format_time(entry.standard))?;
+        if let Some(time) = entry.time {
+            sheet.write_string(row, 4, format_time(time))?;
+        }
+        if let Some(name) = &entry.name {
+            sheet.write_string(row, 5, name)?;
+        }
+        if let Some(gap) = entry.gap() {
+            sheet.write_number_with_format(row, 6, gap, &seconds_format)?;
+        }
+    }
+
+    Ok(())
+}
+
+// Athletes who qualify under only one of the two standards files; a blank
+// standard means that file has no cut for the event
+fn write_status_changes_sheet(workbook: &mut Workbook, rows: &[StatusChange]) -> Result<(), AnalysisError> {
+    let sheet = add_named_sheet(workbook, "Changed Status")?;
+
+    let headers = ["Name", "Sex", "Age Group", "Event", "Best Time", "Previous Standard", "Standard", "Status"];
+    for (col, header) in headers.iter().enumerate() {
+        sheet.write_string(0, col as u16, *header)?;
+    }
+
+    for (i, entry) in rows.iter().enumerate() {
+        let row = (i + 1) as u32;
+        sheet.write_string(row, 0, &entry.name)?;
+        sheet.write_string(row, 1, &entry.sex)?;
+        sheet.write_string(row, 2, &entry.age_group)?;
+        sheet.write_string(row, 3, &entry.event)?;
+        sheet.write_string(row, 4, format_time(entry.time))?;
+        if let Some(standard) = entry.previous_standard {
+            sheet.write_string(row, 5, format_time(standard))?;
+        }
+        if let Some(standard) = entry.standard {
+            sheet.write_string(row, 6, format_time(standard))?;
+        }
+        let status = if entry.newly_qualified { "Newly qualified" } else { "No longer qualified" };
+        sheet.write_string(row, 7, status)?;
+    }
+
+    Ok(())
+}
+
+// Meet data keys with no standard, and how many results each one dropped
+fn write_unmatched_sheet(workbook: &mut Workbook, unmatched: &[UnmatchedKey]) -> Result<(), AnalysisError> {
+    let sheet = add_named_sheet(workbook, "Unmatched")?;
+
+    for (col, header) in ["Sex", "Event", "Age", "Results"].iter().enumerate() {
+        sheet.write_string(0, col as u16, *header)?;
+    }
+
+    for (i, key) in unmatched.iter().enumerate() {
+        let row = (i + 1) as u32;
+        sheet.write_string(row, 0, &key.sex)?;
+        sheet.write_string(row, 1, &key.event)?;
+        sheet.write_string(row, 2, &key.age)?;
+        sheet.write_number(row, 3, key.results as f64)?;
+    }
+
+    Ok(())
+}
+
+// Athletes and swims per sex, entry age and event, qualifying or not
+fn write_participation_sheet(workbook: &mut Workbook, rows: &[Participation]) -> Result<(), AnalysisError> {
+    let sheet = add_named_sheet(workbook, "Participation")?;
+
+    for (col, header) in ["Sex", "Age", "Event", "Athletes", "Swims"].iter().enumerate() {
+        sheet.write_string(0, col as u16, *header)?;
+    }
+
+    for (i, entry) in rows.iter().enumerate() {
+        let row = (i + 1) as u32;
+        sheet.write_string(row, 0, &entry.sex)?;
+        sheet.write_string(row, 1, &entry.age)?;
+        sheet.write_string(row, 2, &entry.event)?;
+        sheet.write_number(row, 3, entry.athletes as f64)?;
+        sheet.write_number(row, 4, entry.swims as f64)?;
+    }
+
+    Ok(())
+}
+
+// Qualifying swims with no standard for their exact age: counted as unique
+// qualifiers under the matched age group but not in the swim counts
+fn write_age_mismatch_sheet(workbook: &mut Workbook, rows: &[AgeMismatch]) -> Result<(), AnalysisError> {
+    let sheet = add_named_sheet(workbook, "Age Mismatch")?;
+
+    let headers = ["Name", "Sex", "Age", "Matched Age Group", "Event", "Time", "Standard"];
+    for (col, header) in headers.iter().enumerate() {
+        sheet.write_string(0, col as u16, *header)?;
+    }
+
+    for (i, entry) in rows.iter().enumerate() {
+        let row = (i + 1) as u32;
+        sheet.write_string(row, 0, &entry.name)?;
+        sheet.write_string(row, 1, &entry.sex)?;
+        sheet.write_string(row, 2, &entry.age)?;
+        sheet.write_string(row, 3, &entry.age_group)?;
+        sheet.write_string(row, 4, &entry.event)?;
+        sheet.write_string(row, 5, format_time(entry.time))?;
+        sheet.write_string(row, 6, format_time(entry.standard))?;
+    }
+
+    Ok(())
+}
+
+// --append: the key columns, then one column of qualifying swims per run.
+// Counts a row didn't have at the time are left blank.
+fn write_history_sheet(workbook: &mut Workbook, history: &CountHistory) -> Result<(), AnalysisError> {
+    let sheet = add_named_sheet(workbook, HISTORY_SHEET)?;
+
+    let headers = HISTORY_KEY_COLUMNS.iter().map(|h| h.to_string()).chain(history.columns.iter().cloned());
+    for (col, header) in headers.enumerate() {
+        sheet.write_string(0, col as u16, header)?;
+    }
+
+    let first_value_col = HISTORY_KEY_COLUMNS.len();
+    for (i, entry) in history.rows.iter().enumerate() {
+        let row = (i + 1) as u32;
+        sheet.write_string(row, 0, &entry.gender)?;
+        if !entry.course.is_empty() {
+            sheet.write_string(row, 1, &entry.course)?;
+        }
+        sheet.write_string(row, 2, &entry.age_group)?;
+        sheet.write_string(row, 3, &entry.event)?;
+        for (col, value) in entry.values.iter().enumerate() {
+            if let Some(value) = value {
+                sheet.write_number(row, (first_value_col + col) as u16, *value)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn write_json(output: &Path, report: &QualifierReport) -> Result<(), AnalysisError> {
+    write_atomic(output, |temp| {
+        let mut writer = BufWriter::new(File::create(temp)?);
+        serde_json::to_writer_pretty(&mut writer, report)?;
+        // Dropping the writer would swallow a failed flush and rename a
+        // truncated file into place
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+        Ok(())
+    })
+}
+
+// Events of a roster row in one CSV cell ("50Fr;100Bk")
+const ROSTER_EVENT_SEPARATOR: &str = ";";
+
+// The qualified athletes as a flat CSV (--roster-csv): Name, Sex, Age Group and
+// the events they qualified in, one row per athlete. The csv writer quotes
+// fields holding commas or quotes, so "Smith, John" stays one column.
+pub fn write_roster_csv(path: &Path, roster: &[RosterEntry]) -> Result<(), AnalysisError> {
+    write_atomic(path, |temp| {
+        let csv_error = |source: csv::Error| AnalysisError::CsvOutput { path: path.to_path_buf(), source };
+        let mut writer = csv::Writer::from_path(temp).map_err(csv_error)?;
+        writer.write_record(["Name", "Sex", "Age Group", "Events"]).map_err(csv_error)?;
+        for entry in roster {
+            let events = entry.events.join(ROSTER_EVENT_SEPARATOR);
+            writer.write_record([&entry.name, &entry.sex, &entry.age_group, &events]).map_err(csv_error)?;
+        }
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+        Ok(())
+    })
+}
+
+// One GitHub-flavored table per gender, laid out like the first grid of its
+// sheet: the --count-mode metric per event/age, named in the header, with
+// totals, then the unique athlete summary rows, and a second table for the
+// relays when there are any. Count columns are right-aligned.
+pub fn write_markdown(
+    output: &Path,
+    report: &QualifierReport,
+    count_mode: CountMode,
+) -> Result<(), AnalysisError> {
+    write_atomic(output, |temp| {
+        let mut file = File::create(temp)?;
+        file.write_all(markdown_report(report, count_mode).as_bytes())?;
+        file.sync_all()?;
+        Ok(())
+    })
+}
+
+pub fn markdown_report(report: &QualifierReport, count_mode: CountMode) -> String {
+    let mut md = String::new();
+    let (main, _) = grid_metrics(count_mode);
+    
+    for gender_report in &report.genders {
+        if !md.is_empty() {
+            md.push('\n');
+        }
+        md.push_str(&format!("## {}\n\n", gender_sheet_name(gender_report)));
+        md.push_str(&markdown_count_table(&main.label("Event"), &gender_report.age_labels, &gender_report.events,
+                                          main.value));
+        
+        // Summary rows leave the Total column empty, as on the sheet
+        let with_total = !has_all_ages(&gender_report.age_labels);
+        let summary_row = |label: &str, value: &dyn Fn(&AgeGroupSummary) -> String| {
+            let mut cells = vec![label.to_string()];
+            cells.extend(gender_report.summary.iter().map(value));
+            if with_total {
+                cells.push(String::new());
+            }
+            markdown_row(&cells)
+        };
+        md.push_str(&summary_row("Total Unique Athletes", &|s| s.total_athletes.to_string()));
+        md.push_str(&summary_row("Unique Qualifiers", &|s| s.unique_qualifiers.to_string()));
+        md.push_str(&summary_row("Qualified %", &|s| {
+            s.qualified_pct.map(|pct| format!("{:.1}%", pct)).unwrap_or_default()
+        }));
+        
+        // Relays get their own table so the totals above stay individual swims
+        if !gender_report.relays.is_empty() {
+            md.push('\n');
+            md.push_str(&markdown_count_table(&main.relay_label("Relay"), &gender_report.age_labels,
+                                              &gender_report.relays, main.value));
+        }
+    }
+    
+    md
+}
+
+// Header, one row of `value` per event and the "Total" row. As on the sheet,
+// an "All Ages" column replaces the per-event Total column.
+fn markdown_count_table(
+    label: &str,
+    age_labels: &[String],
+    events: &[EventCounts],
+    value: fn(&AgeGroupCount) -> usize,
+) -> String {
+    let mut md = String::new();
+    let with_total = !has_all_ages(age_labels);
+    
+    let mut header = vec![label.to_string()];
+    header.extend(age_labels.iter().cloned());
+    if with_total {
+        header.push("Total".to_string());
+    }
+    md.push_str(&markdown_row(&header));
+    
+    let mut align = vec![":---".to_string()];
+    align.extend(std::iter::repeat_n("---:".to_string(), header.len() - 1));
+    md.push_str(&markdown_row(&align));
+    
+    let mut column_totals = vec![0usize; age_labels.len()];
+    for event in events {
+        let mut cells = vec![event.event.clone()];
+        if event.no_standard {
+            cells.push(NO_STANDARD.to_string());
+            cells.resize(header.len(), String::new());
+            md.push_str(&markdown_row(&cells));
+            continue;
+        }
+        for (col, count) in event.counts.iter().enumerate() {
+            cells.push(value(count).to_string());
+            column_totals[col] += value(count);
+        }
+        if with_total {
+            cells.push(event.counts.iter().map(value).sum::<usize>().to_string());
+        }
+        md.push_str(&markdown_row(&cells));
+    }
+    
+    let mut totals = vec!["Total".to_string()];
+    totals.extend(column_totals.iter().map(|t| t.to_string()));
+    if with_total {
+        totals.push(column_totals.iter().sum::<usize>().to_string());
+    }
+    md.push_str(&markdown_row(&totals));
+    
+    md
+}
+
+fn markdown_row(cells: &[String]) -> String {
+    format!("| {} |\n", cells.join(" | "))
+}
@@ -0,0 +1,482 @@
+use calamine::{open_workbook, Data, Range, Reader, Xlsx, XlsxError};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::age::{normalize_age, parse_age_range};
+use crate::config::column_letter;
+use crate::error::AnalysisError;
+use crate::normalize::{event_parts, normalize_any_event_with};
+use crate::time::{time_to_seconds, TimeCell};
+
+pub type StandardKey = (String, String, String); // (sex, age, event)
+pub type CutLevelKey = (String, String, String, String); // (sex, age, event, cut_level)
+pub type CutTimes = HashMap<String, f64>; // {cut_level: qualifying_time}
+pub type AgeGroupStandards = HashMap<String, CutTimes>; // {age_group: {cut_level: time}}
+pub type EventStandards = HashMap<String, AgeGroupStandards>; // {event: {age: {cut: time}}}
+pub type EventOrders = HashMap<String, Vec<String>>; // {gender: [event, ...]} in the standards' order
+// Age group headers whose label says more than the age they're matched on:
+// {(gender, age): label}, e.g. ("Men", "12") -> "12&U"
+pub type AgeLabels = HashMap<(String, String), String>;
+
+// Cut level for headers that are just an age ("11", "10&U")
+pub const DEFAULT_CUT_LEVEL: &str = "Standard";
+
+// The time that counts as qualifying: the slowest cut, since making any level
+// qualifies
+pub fn qualifying_time(cuts: &CutTimes) -> Option<f64> {
+    cuts.values().copied().reduce(f64::max)
+}
+
+// Whether the time makes the cut, allowing `tolerance` seconds over it
+// (touchpad timing a hundredth slow). The cut itself is never changed, so
+// reports show it as published. The sum is rounded to the thousandth so float
+// noise from the addition can't decide a swim that lands exactly on
+// cut + tolerance.
+pub fn makes_cut(time: f64, cut: f64, tolerance: f64) -> bool {
+    if tolerance == 0.0 {
+        return time <= cut;
+    }
+    time <= ((cut + tolerance) * 1000.0).round() / 1000.0
+}
+
+// Fastest cut level the time makes (see makes_cut), e.g. "AA" for a swim
+// between the AA and AAA cuts
+pub fn highest_cut_level(time: f64, cuts: &CutTimes, tolerance: f64) -> Option<&str> {
+    cuts.iter()
+        .filter(|(_, cut)| makes_cut(time, **cut, tolerance))
+        .min_by(|a, b| a.1.total_cmp(b.1))
+        .map(|(level, _)| level.as_str())
+}
+
+// Cut level names from slowest (easiest) to fastest, e.g. A, AA, AAA, ranked by
+// their average position across every event and age group
+pub fn cut_level_order(standards: &HashMap<String, EventStandards>) -> Vec<String> {
+    let mut ranks: HashMap<String, (usize, usize)> = HashMap::new(); // {level: (rank sum, count)}
+    
+    for cuts in standards.values().flat_map(|e| e.values()).flat_map(|a| a.values()) {
+        let mut levels: Vec<(&String, &f64)> = cuts.iter().collect();
+        levels.sort_by(|a, b| b.1.total_cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        for (rank, (level, _)) in levels.iter().enumerate() {
+            let entry = ranks.entry(level.to_string()).or_insert((0, 0));
+            entry.0 += rank;
+            entry.1 += 1;
+        }
+    }
+    
+    let mut order: Vec<(String, f64)> = ranks.into_iter()
+        .map(|(level, (sum, count))| (level, sum as f64 / count as f64))
+        .collect();
+    order.sort_by(|a, b| a.1.total_cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+    order.into_iter().map(|(level, _)| level).collect()
+}
+
+// Splits a standards header into age group and cut level: "12 AA" and "AA 12"
+// give ("12", "AA"); a plain age ("12", "10&U", "11-12") gets DEFAULT_CUT_LEVEL
+fn split_cut_level(header: &str) -> (String, String) {
+    let (age, cut_level) = split_header(header);
+    (normalize_age(&age), cut_level)
+}
+
+// split_cut_level with the age group as written: "12&U AA" gives ("12&U", "AA")
+fn split_header(header: &str) -> (String, String) {
+    let header = header.trim();
+    if parse_age_range(&normalize_age(header)).is_some() {
+        return (header.to_string(), DEFAULT_CUT_LEVEL.to_string());
+    }
+    
+    let tokens: Vec<&str> = header.split_whitespace().collect();
+    if tokens.len() >= 2 {
+        let is_level = |t: &str| t.chars().all(|c| c.is_ascii_alphabetic());
+        let last = tokens[tokens.len() - 1];
+        let rest = tokens[..tokens.len() - 1].join(" ");
+        if is_level(last) && parse_age_range(&normalize_age(&rest)).is_some() {
+            return (rest, last.to_string());
+        }
+        
+        let first = tokens[0];
+        let rest = tokens[1..].join(" ");
+        if is_level(first) && parse_age_range(&normalize_age(&rest)).is_some() {
+            return (rest, first.to_string());
+        }
+    }
+    
+    (header.to_string(), DEFAULT_CUT_LEVEL.to_string())
+}
+
+// Sheet columns (0 = A) of a standards tab's event names and its first age
+// group header; the age groups run from there to the last column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StandardsColumns {
+    pub event: usize,
+    pub first_age: usize,
+}
+
+impl Default for StandardsColumns {
+    // Events in column A, age groups from column B
+    fn default() -> Self {
+        StandardsColumns { event: 0, first_age: 1 }
+    }
+}
+
+// Where `column` falls in a row of `range`, which starts at the first used
+// column rather than at A
+fn range_column(range: &Range<Data>, column: usize) -> Option<usize> {
+    let first_col = range.start().map_or(0, |(_, col)| col as usize);
+    column.checked_sub(first_col)
+}
+
+fn event_cell<'a>(range: &Range<Data>, row: &'a [Data], columns: StandardsColumns) -> Option<&'a Data> {
+    range_column(range, columns.event).and_then(|col| row.get(col))
+}
+
+// A standards tab has an event column followed by age group headers
+// ("10&U", "11", "11-12", "15&O", "12 AA", ...); at least one of them must
+// read as an age
+fn looks_like_standards_tab(range: &Range<Data>, columns: StandardsColumns) -> bool {
+    let header_row = match range.rows().next() {
+        Some(row) => row,
+        None => return false,
+    };
+    let first_age = match range_column(range, columns.first_age) {
+        Some(col) => col,
+        None => return false,
+    };
+
+    header_row.iter().skip(first_age).any(|cell| match cell {
+        Data::String(s) => parse_age_range(&split_cut_level(s).0).is_some(),
+        Data::Int(_) | Data::Float(_) => true,
+        _ => false,
+    })
+}
+
+// Reads one gender tab into `standards`, appending events in sheet order.
+// Headers labeled beyond their age ("12&U", "15 & Over") go into `age_labels`
+// by normalized age; the first tab of a gender to label an age wins. Each
+// non-empty cell that isn't a time is logged and added to `failed_cells`.
+// Returns what's wrong with the tab's layout, if anything.
+#[allow(clippy::too_many_arguments)]
+fn read_standards_tab(
+    range: &Range<Data>,
+    tab: &str,
+    columns: StandardsColumns,
+    stroke_names: &HashMap<String, String>,
+    standards: &mut EventStandards,
+    event_order: &mut Vec<String>,
+    age_labels: &mut HashMap<String, String>,
+    failed_cells: &mut usize,
+) -> Vec<String> {
+    let mut age_groups: Vec<(String, String)> = Vec::new(); // (age, cut level)
+    // No age columns when the configured one is left of the used range
+    let first_age = range_column(range, columns.first_age).unwrap_or(usize::MAX);
+    
+    // Read header row to get age groups (columns.first_age onwards)
+    if let Some(header_row) = range.rows().next() {
+        debug!("Processing {} tab", tab);
+        debug!("  Header row cells:");
+        for (idx, cell) in header_row.iter().enumerate() {
+            let cell_str = match cell {
+                Data::String(s) => s.clone(),
+                Data::Int(i) => i.to_string(),
+                Data::Float(f) => f.to_string(),
+                Data::Empty => "(empty)".to_string(),
+                _ => format!("{:?}", cell),
+            };
+            debug!("    Column {}: '{}'", idx, cell_str);
+        }
+        
+        for cell in header_row.iter().skip(first_age) {
+            let age_str = match cell {
+                Data::String(s) => s.trim().to_string(),
+                Data::Int(i) => i.to_string(),
+                Data::Float(f) => f.to_string(),
+                _ => String::new(),
+            };
+            
+            if !age_str.is_empty() {
+                let (label, cut_level) = split_header(&age_str);
+                let age = normalize_age(&label);
+                if parse_age_range(&age).is_some() && label != age {
+                    age_labels.entry(age.clone()).or_insert(label);
+                }
+                age_groups.push((age, cut_level));
+            }
+        }
+    }
+    
+    debug!("  Age groups found: {:?}", age_groups);
+    
+    // Process data rows; the range start turns indexes into sheet positions
+    let (first_row, first_col) = range.start().map_or((0, 0), |(row, col)| (row as usize, col as usize));
+    let mut row_count = 0;
+    let mut timed_events = 0;
+    for (row_idx, row) in range.rows().enumerate().skip(1) {
+        if row.is_empty() {
+            continue;
+        }
+        
+        let event_str = match event_cell(range, row, columns) {
+            Some(Data::String(s)) => s.trim(),
+            _ => continue,
+        };
+        
+        if event_str.is_empty() {
+            continue;
+        }
+        
+        let normalized_event = match normalize_any_event_with(event_str, stroke_names) {
+            Some((e, _)) => e,
+            None => continue,
+        };
+        
+        if row_count < 3 {
+            debug!("  Sample event row {}: '{}' -> '{}'", row_count, event_str, normalized_event);
+        }
+        
+        if !event_order.contains(&normalized_event) {
+            event_order.push(normalized_event.clone());
+        }
+        
+        // Read times for each age group, lined up with the header
+        let mut age_standards: AgeGroupStandards = HashMap::new();
+        
+        for (idx, (age_group, cut_level)) in age_groups.iter().enumerate() {
+            let col_idx = first_age + idx;
+            if col_idx < row.len() {
+                let cell_value = &row[col_idx];
+                if row_count < 1 && idx < 3 {
+                    debug!("    Age '{}' {} (col {}): cell = {:?}", age_group, cut_level, col_idx, cell_value);
+                }
+                
+                match time_to_seconds(cell_value) {
+                    TimeCell::Empty => {}
+                    cell => match cell.seconds() {
+                        Some(time_value) => {
+                            age_standards.entry(age_group.clone())
+                                .or_default()
+                                .insert(cut_level.clone(), time_value);
+                            if row_count < 1 && idx < 3 {
+                                debug!("      -> Parsed as {:.2}s", time_value);
+                            }
+                        }
+                        // A stray text cell would otherwise just be a missing cut
+                        None => {
+                            *failed_cells += 1;
+                            warn!("Standards tab '{}' cell {}{}: can't read '{}' as a time; {} has no {} cut for {}",
+                                  tab, column_letter(first_col + col_idx), first_row + row_idx + 1, cell_value,
+                                  event_str, cut_level, age_group);
+                        }
+                    },
+                }
+            }
+        }
+        
+        if row_count < 1 {
+            debug!("    Total ages with standards for this event: {}", age_standards.len());
+        }
+        
+        if !age_standards.is_empty() {
+            timed_events += 1;
+        }
+        standards.insert(normalized_event, age_standards);
+        row_count += 1;
+    }
+    
+    debug!("  Total events loaded: {}", row_count);
+    
+    let mut problems = Vec::new();
+    if age_groups.is_empty() {
+        problems.push(format!("tab '{}': header row has no age group columns from column {} (expected headers like 10&U, 11, 12)",
+                              tab, column_letter(columns.first_age)));
+    } else if timed_events == 0 {
+        problems.push(format!("tab '{}': no event rows with a parseable time", tab));
+    }
+    problems
+}
+
+// Gender key a standards tab feeds, matching tab names case-insensitively and
+// ignoring surrounding spaces ("MENS", "mens " and "Mens" are one tab)
+fn gender_for_tab(gender_tabs: &HashMap<String, String>, tab: &str) -> Option<String> {
+    gender_tabs.iter()
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case(tab.trim()))
+        .map(|(_, key)| key.clone())
+}
+
+// One event column entry of a standards tab: the name as written and the
+// normalized event it was read as. `known_stroke` is false when the event's
+// stroke isn't one of the stroke_names abbreviations, so no meet sheet will
+// normalize to it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StandardsEventName {
+    pub gender: String,
+    pub tab: String,
+    pub raw: String,
+    pub event: String,
+    pub known_stroke: bool,
+}
+
+// Every event name in the event column of the mapped gender tabs, in sheet
+// order, as read by load_time_standards. For checking how the names normalize
+// before a run (--list-events).
+pub fn standards_event_names(
+    standards_file: &Path,
+    gender_tabs: &HashMap<String, String>,
+    columns: StandardsColumns,
+    stroke_names: &HashMap<String, String>,
+) -> Result<Vec<StandardsEventName>, AnalysisError> {
+    let mut workbook: Xlsx<_> = open_workbook(standards_file)
+        .map_err(|source: XlsxError| AnalysisError::Workbook {
+            path: standards_file.to_path_buf(),
+            source: source.into(),
+        })?;
+    let mut names = Vec::new();
+    
+    let sheet_names: Vec<String> = workbook.sheet_names().iter().map(|s| s.to_string()).collect();
+    for tab in &sheet_names {
+        let gender = match gender_for_tab(gender_tabs, tab) {
+            Some(gender) => gender,
+            None => continue,
+        };
+        let range = workbook.worksheet_range(tab)
+            .map_err(|source| AnalysisError::SheetRead { sheet: tab.clone(), source: source.into() })?;
+        
+        for row in range.rows().skip(1) {
+            let raw = match event_cell(&range, row, columns) {
+                Some(Data::String(s)) => s.trim(),
+                _ => continue,
+            };
+            let event = match normalize_any_event_with(raw, stroke_names) {
+                Some((event, _)) => event,
+                None => continue,
+            };
+            let known_stroke = event_parts(&event)
+                .is_some_and(|parts| stroke_names.values().any(|abbrev| *abbrev == parts.stroke));
+            names.push(StandardsEventName {
+                gender: gender.clone(),
+                tab: tab.clone(),
+                raw: raw.to_string(),
+                event,
+                known_stroke,
+            });
+        }
+    }
+    
+    Ok(names)
+}
+
+// Loads every standards tab in the workbook. `gender_tabs` maps tab names
+// (case-insensitive) to the gender key results are matched on, e.g.
+// "Mens" -> "Men". Tabs that look like standards but aren't mapped are logged
+// and skipped. Event names in column A are normalized with `stroke_names`;
+// load_time_standards_with reads them (and the age groups) elsewhere.
+//
+// Fails with InvalidStandards, listing every problem found, when a gender in
+// `required_genders` has no tab or a mapped tab has no age group header or no
+// event row with a time.
+pub fn load_time_standards(
+    standards_file: &Path,
+    gender_tabs: &HashMap<String, String>,
+    stroke_names: &HashMap<String, String>,
+    required_genders: &[String],
+) -> Result<(HashMap<String, EventStandards>, EventOrders), AnalysisError> {
+    load_time_standards_with(standards_file, gender_tabs, StandardsColumns::default(), stroke_names,
+                             required_genders)
+}
+
+// load_time_standards for a template with the events and age groups in other
+// columns, e.g. a category column before the event names
+pub fn load_time_standards_with(
+    standards_file: &Path,
+    gender_tabs: &HashMap<String, String>,
+    columns: StandardsColumns,
+    stroke_names: &HashMap<String, String>,
+    required_genders: &[String],
+) -> Result<(HashMap<String, EventStandards>, EventOrders), AnalysisError> {
+    let (standards, event_orders, _) =
+        load_time_standards_labeled(standards_file, gender_tabs, columns, stroke_names, required_genders)?;
+    Ok((standards, event_orders))
+}
+
+// load_time_standards_with plus the age group labels as the headers write them
+// ("12&U"), for the output columns; the counts match on the bare age
+pub fn load_time_standards_labeled(
+    standards_file: &Path,
+    gender_tabs: &HashMap<String, String>,
+    columns: StandardsColumns,
+    stroke_names: &HashMap<String, String>,
+    required_genders: &[String],
+) -> Result<(HashMap<String, EventStandards>, EventOrders, AgeLabels), AnalysisError> {
+    if columns.first_age <= columns.event {
+        let problem = format!("the first age group column ({}) must be right of the event column ({})",
+                              column_letter(columns.first_age), column_letter(columns.event));
+        return Err(AnalysisError::InvalidStandards { path: standards_file.to_path_buf(), problems: vec![problem] });
+    }
+    
+    let mut workbook: Xlsx<_> = open_workbook(standards_file)
+        .map_err(|source: XlsxError| AnalysisError::Workbook {
+            path: standards_file.to_path_buf(),
+            source: source.into(),
+        })?;
+    let mut all_standards: HashMap<String, EventStandards> = HashMap::new();
+    let mut event_orders: EventOrders = HashMap::new();
+    let mut gender_labels: HashMap<String, HashMap<String, String>> = HashMap::new();
+    let mut problems: Vec<String> = Vec::new();
+    let mut failed_cells: HashMap<String, usize> = HashMap::new();
+    
+    let sheet_names: Vec<String> = workbook.sheet_names().iter().map(|s| s.to_string()).collect();
+    
+    for tab in &sheet_names {
+        let gender_key = match gender_for_tab(gender_tabs, tab) {
+            Some(key) => key,
+            None => {
+                match workbook.worksheet_range(tab) {
+                    Ok(range) if looks_like_standards_tab(&range, columns) => {
+                        warn!("Skipping standards tab '{}': not listed in gender_tabs (add \"{}\" = \"<gender>\" to use it)",
+                              tab, tab.trim());
+                    }
+                    _ => debug!("Skipping sheet '{}': no age group header row", tab),
+                }
+                continue;
+            }
+        };
+        
+        let range = workbook.worksheet_range(tab)
+            .map_err(|source| AnalysisError::SheetRead { sheet: tab.clone(), source: source.into() })?;
+        
+        // Several tabs may feed one gender (e.g. "Mens" and "Boys")
+        let standards = all_standards.entry(gender_key.clone()).or_default();
+        let event_order = event_orders.entry(gender_key.clone()).or_default();
+        let labels = gender_labels.entry(gender_key.clone()).or_default();
+        let failed = failed_cells.entry(gender_key).or_default();
+        problems.extend(read_standards_tab(&range, tab, columns, stroke_names, standards, event_order, labels,
+                                           failed));
+    }
+    
+    let mut failed_cells: Vec<(String, usize)> = failed_cells.into_iter().filter(|(_, n)| *n > 0).collect();
+    failed_cells.sort();
+    for (gender, count) in &failed_cells {
+        warn!("{}: {} standard cells couldn't be read as times and were left out", gender, count);
+    }
+    
+    for gender in required_genders {
+        if !all_standards.contains_key(gender) {
+            let mut tabs: Vec<&str> = gender_tabs.iter()
+                .filter(|(_, key)| *key == gender)
+                .map(|(tab, _)| tab.as_str())
+                .collect();
+            tabs.sort();
+            problems.push(format!("no tab for {} (expected one of: {})", gender, tabs.join(", ")));
+        }
+    }
+    
+    if !problems.is_empty() {
+        return Err(AnalysisError::InvalidStandards { path: standards_file.to_path_buf(), problems });
+    }
+    
+    let age_labels = gender_labels.into_iter()
+        .flat_map(|(gender, labels)| labels.into_iter().map(move |(age, label)| ((gender.clone(), age), label)))
+        .collect();
+    
+    Ok((all_standards, event_orders, age_labels))
+}
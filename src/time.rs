@@ -0,0 +1,163 @@
+use calamine::Data;
+use log::warn;
+
+// Rewrites a decimal comma in the seconds component ("1:02,34" -> "1:02.34").
+// Anything else containing a comma is left alone.
+fn normalize_decimal_comma(s: &str) -> String {
+    let (head, seconds) = match s.rfind(':') {
+        Some(idx) => s.split_at(idx + 1),
+        None => ("", s),
+    };
+    
+    match seconds.split_once(',') {
+        Some((whole, fraction))
+            if !whole.is_empty()
+                && !fraction.is_empty()
+                && whole.chars().all(|c| c.is_ascii_digit())
+                && fraction.chars().all(|c| c.is_ascii_digit()) =>
+        {
+            format!("{}{}.{}", head, whole, fraction)
+        }
+        _ => s.to_string(),
+    }
+}
+
+// Result status codes written in the time column instead of a time
+pub const STATUS_CODES: &[&str] = &["DQ", "NS", "DNS", "SCR", "DNF", "DFS", "NT"];
+
+// Placeholders exports write where there is no time (case-insensitive). They
+// read as an empty cell, as does a zero time ("0.00"), in both the meet files
+// and the standards.
+pub const NO_RESULT_SENTINELS: &[&str] = &["nan", "-", "--", "---", "n/a", "na", "none"];
+
+// Whether a trimmed cell is one of NO_RESULT_SENTINELS
+pub fn is_no_result(s: &str) -> bool {
+    NO_RESULT_SENTINELS.iter().any(|sentinel| s.eq_ignore_ascii_case(sentinel))
+}
+
+// Some exports format "1:32.00" as a time of day, 12:01:32 PM, where the 12 is
+// a 12-hour clock's zero hour. calamine tells a duration format ([h]:mm:ss)
+// apart from a date/time one but not whether it shows AM/PM, so a date/time
+// cell from noon on is taken as a clock time. Anything shorter, like a
+// 1:02:03.5 open water swim, is a duration.
+pub const CLOCK_TIME_SECONDS: f64 = 12.0 * 3600.0;
+
+// What a time cell contained
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimeCell {
+    Time(f64),
+    ClockTime(f64), // Time-of-day cell ("12:01:32 PM") read as minutes and seconds
+    Status(String), // DQ, NS, SCR, DNF...
+    Empty,
+    Unparseable,
+}
+
+impl TimeCell {
+    pub fn seconds(&self) -> Option<f64> {
+        match self {
+            TimeCell::Time(t) | TimeCell::ClockTime(t) => Some(*t),
+            _ => None,
+        }
+    }
+}
+
+fn parse_time_string(s: &str) -> Option<f64> {
+    if s.contains(':') {
+        let parts: Vec<&str> = s.split(':').collect();
+        return match parts.len() {
+            // MM:SS.xx
+            2 => {
+                let minutes = parts[0].parse::<f64>().ok()?;
+                let seconds = parts[1].parse::<f64>().ok()?;
+                // "2:65.00" is a typo or swapped fields, not 3:05.00
+                if seconds >= 60.0 {
+                    warn!("Time '{}' has {} seconds; reading it as unparseable", s, parts[1]);
+                    return None;
+                }
+                Some(minutes * 60.0 + seconds)
+            }
+            // H:MM:SS.xx for long distance swims
+            3 => {
+                let hours = parts[0].parse::<f64>().ok()?;
+                let minutes = parts[1].parse::<f64>().ok()?;
+                let seconds = parts[2].parse::<f64>().ok()?;
+                // Past the hour field, both must stay under 60 ("1:75:99")
+                if minutes >= 60.0 || seconds >= 60.0 {
+                    warn!("Time '{}' has {} minutes and {} seconds; reading it as unparseable",
+                          s, parts[1], parts[2]);
+                    return None;
+                }
+                Some(hours * 3600.0 + minutes * 60.0 + seconds)
+            }
+            _ => None,
+        };
+    }
+    
+    s.parse::<f64>().ok()
+}
+
+pub fn time_to_seconds(value: &Data) -> TimeCell {
+    match value {
+        Data::Float(f) if *f == 0.0 => TimeCell::Empty,
+        Data::Float(f) => TimeCell::Time(*f),
+        Data::Int(0) => TimeCell::Empty,
+        Data::Int(i) => TimeCell::Time(*i as f64),
+        Data::DateTime(dt) => {
+            // Excel stores time as fraction of a day
+            // Convert to seconds: fraction_of_day * 24 hours * 60 minutes * 60 seconds
+            let seconds = dt.as_f64() * 86400.0; // 24 * 60 * 60 = 86400 seconds per day
+            
+            // A clock time (see CLOCK_TIME_SECONDS) keeps its minutes and
+            // seconds; the hour is noise. A whole day or more is a date, not a
+            // time. Cells typed as durations are taken as they are.
+            if seconds == 0.0 {
+                TimeCell::Empty
+            } else if dt.is_duration() {
+                TimeCell::Time(seconds)
+            } else if dt.as_f64() >= 1.0 {
+                TimeCell::Unparseable
+            } else if seconds >= CLOCK_TIME_SECONDS {
+                TimeCell::ClockTime(seconds % 3600.0)
+            } else {
+                TimeCell::Time(seconds)
+            }
+        }
+        Data::String(s) => {
+            let s = s.trim();
+            if s.is_empty() || is_no_result(s) {
+                return TimeCell::Empty;
+            }
+            
+            // Status codes may carry a note ("DQ 7.2 false start")
+            if let Some(code) = s.split_whitespace().next() {
+                let code = code.to_ascii_uppercase();
+                if STATUS_CODES.contains(&code.as_str()) {
+                    return TimeCell::Status(code);
+                }
+            }
+            
+            // European exports write "1:02,34"
+            match parse_time_string(&normalize_decimal_comma(s)) {
+                Some(0.0) => TimeCell::Empty,
+                Some(seconds) => TimeCell::Time(seconds),
+                None => TimeCell::Unparseable,
+            }
+        }
+        Data::Empty => TimeCell::Empty,
+        _ => TimeCell::Unparseable,
+    }
+}
+
+// Swim time for display: "59.99" under a minute, "2:05.50" from 60 seconds up.
+// Rounded to hundredths before splitting, so 59.999 shows as "1:00.00".
+pub fn format_time(seconds: f64) -> String {
+    let hundredths = (seconds * 100.0).round() as u64;
+    let minutes = hundredths / 6000;
+    let rest = hundredths % 6000;
+    
+    if minutes == 0 {
+        format!("{}.{:02}", rest / 100, rest % 100)
+    } else {
+        format!("{}:{:02}.{:02}", minutes, rest / 100, rest % 100)
+    }
+}
@@ -0,0 +1,143 @@
+use log::{log, Level};
+use serde::Serialize;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::error::AnalysisError;
+use crate::output::write_atomic;
+
+// Kind of data-quality problem, written as snake_case in warnings.json
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningCategory {
+    UnreadableFile,
+    ProtectedFile,
+    CorruptFile,
+    DuplicateFile,
+    UndatedFile,
+    SkippedSheet,
+    UnreadableSheet,
+    NoEventSheets,
+    ShortRows,
+    DuplicateRows,
+    UnparseableTime,
+    ClockTime,
+    UnmatchedEvent,
+    UnmappedSex,
+}
+
+impl WarningCategory {
+    // Name used in warnings.json
+    pub fn name(self) -> &'static str {
+        match self {
+            WarningCategory::UnreadableFile => "unreadable_file",
+            WarningCategory::ProtectedFile => "protected_file",
+            WarningCategory::CorruptFile => "corrupt_file",
+            WarningCategory::DuplicateFile => "duplicate_file",
+            WarningCategory::UndatedFile => "undated_file",
+            WarningCategory::SkippedSheet => "skipped_sheet",
+            WarningCategory::UnreadableSheet => "unreadable_sheet",
+            WarningCategory::NoEventSheets => "no_event_sheets",
+            WarningCategory::ShortRows => "short_rows",
+            WarningCategory::DuplicateRows => "duplicate_rows",
+            WarningCategory::UnparseableTime => "unparseable_time",
+            WarningCategory::ClockTime => "clock_time",
+            WarningCategory::UnmatchedEvent => "unmatched_event",
+            WarningCategory::UnmappedSex => "unmapped_sex",
+        }
+    }
+}
+
+// One soft failure from a run: something skipped or dropped without stopping
+// the analysis. `row` is the 1-based spreadsheet row when it is about one row.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Warning {
+    pub file: Option<String>,
+    pub sheet: Option<String>,
+    pub row: Option<usize>,
+    pub category: WarningCategory,
+    pub message: String,
+}
+
+impl Warning {
+    pub fn new(category: WarningCategory, message: impl Into<String>) -> Self {
+        Warning { file: None, sheet: None, row: None, category, message: message.into() }
+    }
+
+    pub fn in_file(mut self, file: impl Into<String>) -> Self {
+        self.file = Some(file.into());
+        self
+    }
+
+    pub fn in_sheet(mut self, sheet: impl Into<String>) -> Self {
+        self.sheet = Some(sheet.into());
+        self
+    }
+
+    pub fn at_row(mut self, row: usize) -> Self {
+        self.row = Some(row);
+        self
+    }
+}
+
+// "file sheet 'X' row 12: message", leaving out whatever isn't known
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut location = Vec::new();
+        if let Some(file) = &self.file {
+            location.push(file.clone());
+        }
+        if let Some(sheet) = &self.sheet {
+            location.push(format!("sheet '{}'", sheet));
+        }
+        if let Some(row) = self.row {
+            location.push(format!("row {}", row));
+        }
+        
+        if location.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", location.join(" "), self.message)
+        }
+    }
+}
+
+// Logs the warning at `level` and keeps it for warnings.json, so the file
+// also lists problems only shown with -v
+pub fn record_warning(warnings: &mut Vec<Warning>, level: Level, warning: Warning) {
+    log!(level, "{}", warning);
+    warnings.push(warning);
+}
+
+// "out/qualifier_counts.xlsx" -> "out/warnings.json"
+pub fn warnings_path(output: &Path) -> PathBuf {
+    output.with_file_name("warnings.json")
+}
+
+// Count per category, most frequent first: "3 unparseable_time, 1 skipped_sheet"
+pub fn summarize_warnings(warnings: &[Warning]) -> String {
+    let mut counts: Vec<(WarningCategory, usize)> = Vec::new();
+    for warning in warnings {
+        match counts.iter_mut().find(|(category, _)| *category == warning.category) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((warning.category, 1)),
+        }
+    }
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.name().cmp(b.0.name())));
+    counts.iter()
+        .map(|(category, count)| format!("{} {}", count, category.name()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub fn write_warnings(path: &Path, warnings: &[Warning]) -> Result<(), AnalysisError> {
+    write_atomic(path, |temp| {
+        let mut writer = BufWriter::new(File::create(temp)?);
+        serde_json::to_writer_pretty(&mut writer, warnings)?;
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+        Ok(())
+    })
+}
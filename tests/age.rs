@@ -0,0 +1,217 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use standards::{
+    age_sort_key, apply_over_age_policy, find_best_age_match, find_best_age_range_match,
+    is_over_age, match_age_group, normalize_age, parse_age_range, unparseable_ages,
+    AgeGroupStandards, AnalysisError, EventStandards, MeetResult, OverAgePolicy,
+};
+
+fn ages(list: &[&str]) -> Vec<String> {
+    list.iter().map(|a| a.to_string()).collect()
+}
+
+#[test]
+fn range_headers_keep_both_ends() {
+    assert_eq!(normalize_age("11-12"), "11-12");
+    assert_eq!(normalize_age("13 - 14"), "13-14");
+    assert_eq!(parse_age_range("11-12"), Some((11, 12)));
+    assert_eq!(parse_age_range("12"), Some((12, 12)));
+    assert_eq!(parse_age_range("12-11"), None);
+}
+
+#[test]
+fn and_under_is_the_plain_age() {
+    assert_eq!(normalize_age("10&U"), "10");
+    assert_eq!(normalize_age("15&O"), "15");
+    assert_eq!(normalize_age("15 & Over"), "15");
+    assert_eq!(normalize_age("13"), "13");
+    assert_eq!(age_sort_key(&normalize_age("15&O")), 15);
+    assert_eq!(age_sort_key(&normalize_age("13")), 13);
+}
+
+#[test]
+fn age_inside_a_range_matches_it() {
+    let available = ages(&["10", "11-12", "13-14"]);
+    assert_eq!(find_best_age_match("11", &available).as_deref(), Some("11-12"));
+    assert_eq!(find_best_age_match("12", &available).as_deref(), Some("11-12"));
+    assert_eq!(find_best_age_match("13", &available).as_deref(), Some("13-14"));
+    assert_eq!(find_best_age_match("10", &available).as_deref(), Some("10"));
+}
+
+#[test]
+fn ages_outside_every_range_use_the_nearest() {
+    let available = ages(&["10", "11-12", "13-14"]);
+    assert_eq!(find_best_age_match("8", &available).as_deref(), Some("10"));
+    assert_eq!(find_best_age_match("16", &available).as_deref(), Some("13-14"));
+
+    // 12 falls in the gap and is closer to 13-14 than to 10
+    let gapped = ages(&["10", "13-14"]);
+    assert_eq!(find_best_age_match("12", &gapped).as_deref(), Some("13-14"));
+    assert_eq!(find_best_age_match("11", &gapped).as_deref(), Some("10"));
+}
+
+#[test]
+fn single_age_wins_over_an_overlapping_range() {
+    let available = ages(&["11-12", "12"]);
+    assert_eq!(find_best_age_match("12", &available).as_deref(), Some("12"));
+}
+
+// {event: {age: {"Standard": 30.0}}}
+fn event_standards(events: &[(&str, &[&str])]) -> EventStandards {
+    events.iter()
+        .map(|(event, ages)| {
+            let age_groups: AgeGroupStandards = ages.iter()
+                .map(|age| (age.to_string(), HashMap::from([("Standard".to_string(), 30.0)])))
+                .collect();
+            (event.to_string(), age_groups)
+        })
+        .collect()
+}
+
+#[test]
+fn exact_age_matches_its_own_group() {
+    let standards = event_standards(&[("50Fr", &["10", "11", "12"])]);
+    assert_eq!(match_age_group("11", "11", "50Fr", &standards).as_deref(), Some("11"));
+}
+
+#[test]
+fn younger_than_the_youngest_group_uses_it() {
+    let standards = event_standards(&[("50Fr", &["10", "11", "12"])]);
+    assert_eq!(match_age_group("8", "8", "50Fr", &standards).as_deref(), Some("10"));
+}
+
+#[test]
+fn older_than_the_oldest_group_uses_it() {
+    let standards = event_standards(&[("50Fr", &["10", "11", "12"])]);
+    assert_eq!(match_age_group("17", "17", "50Fr", &standards).as_deref(), Some("12"));
+    assert_eq!(match_age_group("15", "16", "50Fr", &standards).as_deref(), Some("12"));
+}
+
+#[test]
+fn empty_standards_match_nothing() {
+    assert_eq!(find_best_age_match("12", &[]), None);
+    assert_eq!(match_age_group("12", "12", "50Fr", &EventStandards::new()), None);
+
+    let no_ages = event_standards(&[("50Fr", &[])]);
+    assert_eq!(match_age_group("12", "12", "50Fr", &no_ages), None);
+}
+
+// Totals used to match every result against the first event's age groups;
+// an event without standards now falls back to every group the gender has
+#[test]
+fn event_without_standards_uses_all_age_groups() {
+    let standards = event_standards(&[("50Fr", &["10", "11"]), ("100Fr", &["12", "13"])]);
+    assert_eq!(match_age_group("13", "13", "200Bk", &standards).as_deref(), Some("13"));
+    assert_eq!(match_age_group("13", "13", "50Fr", &standards).as_deref(), Some("11"));
+}
+
+#[test]
+fn suffixed_ages_are_normalized_before_matching() {
+    let available = ages(&["10", "11", "12"]);
+    assert_eq!(find_best_age_match("12&U", &available).as_deref(), Some("12"));
+    assert_eq!(find_best_age_match("15&O", &ages(&["13", "15"])).as_deref(), Some("15"));
+    assert_eq!(find_best_age_match("13", &ages(&["13", "15"])).as_deref(), Some("13"));
+    assert_eq!(find_best_age_range_match("00", "11 & Under", &available).as_deref(), Some("11"));
+}
+
+#[test]
+fn open_and_senior_match_the_oldest_group() {
+    let available = ages(&["10", "11-12", "13-14"]);
+    assert_eq!(find_best_age_match("Open", &available).as_deref(), Some("13-14"));
+    assert_eq!(find_best_age_match("senior", &available).as_deref(), Some("13-14"));
+    assert_eq!(find_best_age_match("Open", &[]), None);
+}
+
+#[test]
+fn unreadable_ages_match_nothing() {
+    let available = ages(&["10", "11", "12"]);
+    assert_eq!(find_best_age_match("", &available), None);
+    assert_eq!(find_best_age_match("twelve", &available), None);
+}
+
+#[test]
+fn unparseable_ages_are_counted_once_per_result() {
+    let results = vec![
+        men_free_result("Ann Able", "twelve"),
+        men_free_result("Bo Baker", "twelve"),
+        men_free_result("Cy Carter", "?"),
+        men_free_result("Di Dunn", "Senior"),
+        men_free_result("Ed Ely", "12&U"),
+    ];
+    // Matching doesn't add to the count, however often it runs
+    let standards = standards_to_15();
+    for result in &results {
+        match_age_group(&result.age_min, &result.age, &result.event, &standards["Men"]);
+    }
+    assert_eq!(unparseable_ages(&results), BTreeMap::from([("?".to_string(), 1), ("twelve".to_string(), 2)]));
+}
+
+// A Men 50Fr swim at the given age, as the meet parser would produce it
+fn men_free_result(name: &str, age: &str) -> MeetResult {
+    MeetResult {
+        folder: Path::new("data").into(),
+        meet: "Test".into(),
+        date: None,
+        course: "SCY".into(),
+        sex: "Men".into(),
+        age: age.into(),
+        age_min: age.into(),
+        event: "50Fr".into(),
+        time: 25.0,
+        split_time: None,
+        round: None,
+        name: name.to_string(),
+        is_relay: false,
+    }
+}
+
+// Standards topping out at a 15&O group (stored as "15")
+fn standards_to_15() -> HashMap<String, EventStandards> {
+    HashMap::from([("Men".to_string(), event_standards(&[("50Fr", &["13-14", "15"])]))])
+}
+
+#[test]
+fn over_age_only_above_the_oldest_group() {
+    let standards = event_standards(&[("50Fr", &["13-14", "15"])]);
+    assert!(is_over_age("18", "18", "50Fr", &standards));
+    assert!(!is_over_age("15", "15", "50Fr", &standards));
+    assert!(!is_over_age("15", "16", "50Fr", &standards));
+    assert!(!is_over_age("Open", "Open", "50Fr", &standards));
+}
+
+#[test]
+fn clamp_keeps_over_age_results_in_the_oldest_group() {
+    let mut results = vec![men_free_result("Ann Able", "18"), men_free_result("Bo Baker", "14")];
+    let affected = apply_over_age_policy(&mut results, &standards_to_15(), OverAgePolicy::Clamp).unwrap();
+    assert_eq!(affected, 1);
+    assert_eq!(results.len(), 2);
+    let men = &standards_to_15()["Men"];
+    assert_eq!(match_age_group("18", "18", "50Fr", men).as_deref(), Some("15"));
+}
+
+#[test]
+fn drop_removes_over_age_results() {
+    let mut results = vec![men_free_result("Ann Able", "18"), men_free_result("Bo Baker", "14")];
+    let affected = apply_over_age_policy(&mut results, &standards_to_15(), OverAgePolicy::Drop).unwrap();
+    assert_eq!(affected, 1);
+    let names: Vec<_> = results.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, vec!["Bo Baker"]);
+}
+
+#[test]
+fn error_fails_on_over_age_results() {
+    let mut results = vec![men_free_result("Bo Baker", "14"), men_free_result("Ann Able", "18")];
+    let err = apply_over_age_policy(&mut results, &standards_to_15(), OverAgePolicy::Error).unwrap_err();
+    match err {
+        AnalysisError::OverAge { count, example } => {
+            assert_eq!(count, 1);
+            assert_eq!(example, "Ann Able Men 18 50Fr");
+        }
+        other => panic!("expected OverAge, got {}", other),
+    }
+
+    // Nothing over age, nothing to fail on
+    let mut results = vec![men_free_result("Bo Baker", "14")];
+    assert_eq!(apply_over_age_policy(&mut results, &standards_to_15(), OverAgePolicy::Error).unwrap(), 0);
+}
@@ -0,0 +1,51 @@
+// Helpers shared by the integration tests. Each test file compiles this
+// module on its own and uses only part of it.
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+
+use standards::{AgeGroupCount, AgeGroupSummary, EventCounts};
+
+// Temp folder for the generated workbooks, removed when the test ends
+pub struct FixtureDir(pub PathBuf);
+
+impl FixtureDir {
+    pub fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("standards-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        FixtureDir(dir)
+    }
+}
+
+impl Drop for FixtureDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+// A report row with (qualifying swims, qualified athletes) for each of `ages`
+pub fn event(name: &str, ages: &[&str], counts: &[(usize, usize)]) -> EventCounts {
+    EventCounts {
+        event: name.to_string(),
+        counts: counts.iter()
+            .zip(ages)
+            .map(|(&(qualifying_swims, qualified_athletes), age)| AgeGroupCount {
+                age_group: age.to_string(),
+                qualifying_swims,
+                qualified_athletes,
+            })
+            .collect(),
+        no_standard: false,
+    }
+}
+
+// Summary row for an age group, with the Qualified % build_report would give
+pub fn summary(age: &str, total_athletes: usize, unique_qualifiers: usize) -> AgeGroupSummary {
+    AgeGroupSummary {
+        age_group: age.to_string(),
+        total_athletes,
+        unique_qualifiers,
+        qualified_pct: (total_athletes > 0).then(|| unique_qualifiers as f64 / total_athletes as f64 * 100.0),
+    }
+}
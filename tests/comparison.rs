@@ -0,0 +1,35 @@
+use standards::StandardComparison;
+
+fn comparison(time: f64, standard: f64) -> StandardComparison {
+    StandardComparison {
+        name: "Ann Able".to_string(),
+        sex: "Women".to_string(),
+        age_group: "12".to_string(),
+        event: "50Fr".to_string(),
+        time,
+        standard,
+    }
+}
+
+#[test]
+fn percent_of_cut_is_under_100_for_qualifiers() {
+    let pct = comparison(30.76, 31.0).pct_of_standard().unwrap();
+    assert!((pct - 99.2258).abs() < 1e-3);
+
+    let pct = comparison(31.5, 31.0).pct_of_standard().unwrap();
+    assert!(pct > 100.0);
+}
+
+#[test]
+fn percent_of_cut_compares_events_of_any_length() {
+    // 1% under the cut reads the same for a 50 and a 400
+    let sprint = comparison(29.7, 30.0).pct_of_standard().unwrap();
+    let distance = comparison(297.0, 300.0).pct_of_standard().unwrap();
+    assert!((sprint - distance).abs() < 1e-9);
+}
+
+#[test]
+fn no_percent_without_a_usable_standard() {
+    assert_eq!(comparison(30.0, 0.0).pct_of_standard(), None);
+    assert_eq!(comparison(30.0, f64::NAN).pct_of_standard(), None);
+}
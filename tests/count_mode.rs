@@ -0,0 +1,64 @@
+mod common;
+
+use calamine::{open_workbook, Data, Reader, Xlsx};
+
+use standards::{
+    write_workbook, AgeGroupCount, AgeGroupSummary, Config, CountMode, EventCounts, GenderReport,
+    QualifierReport,
+};
+
+use common::FixtureDir;
+
+// Men 12, one 50Fr with three qualifying swims by two athletes
+fn report() -> QualifierReport {
+    QualifierReport {
+        genders: vec![GenderReport {
+            gender: "Men".to_string(),
+            course: None,
+            age_groups: vec!["12".to_string()],
+            age_labels: vec!["12".to_string()],
+            events: vec![EventCounts {
+                event: "50Fr".to_string(),
+                counts: vec![AgeGroupCount {
+                    age_group: "12".to_string(),
+                    qualifying_swims: 3,
+                    qualified_athletes: 2,
+                }],
+                no_standard: false,
+            }],
+            relays: Vec::new(),
+            stroke_totals: Vec::new(),
+            summary: vec![AgeGroupSummary {
+                age_group: "12".to_string(),
+                total_athletes: 4,
+                unique_qualifiers: 2,
+                qualified_pct: Some(50.0),
+            }],
+            cut_levels: Vec::new(),
+        }],
+    }
+}
+
+// Header label and 50Fr count of the first grid on the Mens sheet
+fn main_grid(count_mode: CountMode, name: &str) -> (Data, Data) {
+    let dir = FixtureDir::new(name);
+    let config = Config { output: dir.0.join("qualifier_counts.xlsx"), count_mode, ..Config::default() };
+    write_workbook(&config, &report(), &[], &[], &[], &[], &[], &[], &[], &[], &[], None).unwrap();
+
+    let mut workbook: Xlsx<_> = open_workbook(&config.output).unwrap();
+    let range = workbook.worksheet_range("Mens").unwrap();
+    let cells = (range.get((0, 0)).cloned().unwrap(), range.get((1, 1)).cloned().unwrap());
+    cells
+}
+
+#[test]
+fn swims_mode_counts_every_qualifying_swim() {
+    assert_eq!(main_grid(CountMode::Swims, "count-mode-swims"),
+               (Data::String("Event (Qualifying Swims)".to_string()), Data::Float(3.0)));
+}
+
+#[test]
+fn athletes_mode_counts_each_athlete_once() {
+    assert_eq!(main_grid(CountMode::Athletes, "count-mode-athletes"),
+               (Data::String("Event (Qualified Athletes)".to_string()), Data::Float(2.0)));
+}
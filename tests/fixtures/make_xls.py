@@ -0,0 +1,83 @@
+#!/usr/bin/env python3
+"""Writes the legacy .xls fixture used by tests/workbook_format.rs.
+
+rust_xlsxwriter only writes .xlsx, so this builds a minimal BIFF8 workbook in
+an OLE compound file by hand: one "50 Free" sheet laid out like the meet
+exports (Name in column E, Time in column J). Run from the repository root:
+
+    python3 tests/fixtures/make_xls.py
+"""
+import struct
+
+OUTPUT = "tests/fixtures/CAN-MBSK_Legacy_SCY_Men_11-12.xls"
+SHEET = "50 Free"
+ROWS = [("Name", "Time"), ("Able, Ann", "30.50"), ("Baker, Bo", "32.10")]
+NAME_COL, TIME_COL = 4, 9
+
+SECTOR = 512
+ENDOFCHAIN, FREESECT, FATSECT, NOSTREAM = 0xFFFFFFFE, 0xFFFFFFFF, 0xFFFFFFFD, 0xFFFFFFFF
+MIN_STREAM = 4096  # smaller streams would have to go in the mini stream
+
+
+def record(typ, data=b""):
+    return struct.pack("<HH", typ, len(data)) + data
+
+
+def bof(dt):
+    # BIFF8, build/year as written by Excel 97
+    return record(0x0809, struct.pack("<HHHHII", 0x0600, dt, 0x0DBB, 0x07CC, 0, 0x06))
+
+
+def label(row, col, text):
+    data = text.encode("latin-1")
+    return record(0x0204, struct.pack("<HHHHB", row, col, 0, len(data), 0) + data)
+
+
+def workbook_stream():
+    sheet = bof(0x0010)
+    sheet += record(0x0200, struct.pack("<IIHHH", 0, len(ROWS), 0, TIME_COL + 1, 0))
+    for row, (name, time) in enumerate(ROWS):
+        sheet += label(row, NAME_COL, name) + label(row, TIME_COL, time)
+    sheet += record(0x000A)
+
+    def globals_with_sheet_at(pos):
+        name = SHEET.encode("latin-1")
+        boundsheet = record(0x0085, struct.pack("<IBBBB", pos, 0, 0, len(name), 0) + name)
+        return bof(0x0005) + record(0x0042, struct.pack("<H", 0x04B0)) + boundsheet + record(0x000A)
+
+    # The sheet offset doesn't change the globals' length
+    globals_part = globals_with_sheet_at(0)
+    stream = globals_with_sheet_at(len(globals_part)) + sheet
+    return stream + b"\0" * (MIN_STREAM - len(stream))
+
+
+def dir_entry(name, typ, child, start, size):
+    encoded = (name + "\0").encode("utf-16-le") if name else b""
+    return (encoded.ljust(64, b"\0")
+            + struct.pack("<HBB", len(encoded), typ, 1 if name else 0)
+            + struct.pack("<III", NOSTREAM, NOSTREAM, child)
+            + b"\0" * 16 + struct.pack("<I", 0) + b"\0" * 16
+            + struct.pack("<IQ", start, size))
+
+
+def compound_file(stream):
+    stream_sectors = len(stream) // SECTOR
+    # Sector 0: FAT, sector 1: directory, sectors 2..: the Workbook stream
+    fat = [FATSECT, ENDOFCHAIN] + [s + 1 for s in range(2, 2 + stream_sectors - 1)] + [ENDOFCHAIN]
+    fat += [FREESECT] * (SECTOR // 4 - len(fat))
+
+    directory = (dir_entry("Root Entry", 5, 1, ENDOFCHAIN, 0)
+                 + dir_entry("Workbook", 2, NOSTREAM, 2, len(stream))
+                 + dir_entry("", 0, NOSTREAM, 0, 0) * 2)
+
+    header = bytes.fromhex("D0CF11E0A1B11AE1") + b"\0" * 16
+    header += struct.pack("<HHHHH", 0x003E, 0x0003, 0xFFFE, 9, 6) + b"\0" * 6
+    header += struct.pack("<IIIIIIIII", 0, 1, 1, 0, MIN_STREAM, ENDOFCHAIN, 0, ENDOFCHAIN, 0)
+    header += struct.pack("<I", 0) + struct.pack("<I", FREESECT) * 108
+
+    return header + struct.pack("<%dI" % len(fat), *fat) + directory + stream
+
+
+if __name__ == "__main__":
+    with open(OUTPUT, "wb") as f:
+        f.write(compound_file(workbook_stream()))
@@ -0,0 +1,20 @@
+use standards::format_time;
+
+#[test]
+fn under_a_minute_is_seconds() {
+    assert_eq!(format_time(59.99), "59.99");
+    assert_eq!(format_time(28.4), "28.40");
+    assert_eq!(format_time(9.05), "9.05");
+}
+
+#[test]
+fn a_minute_and_over_has_minutes() {
+    assert_eq!(format_time(60.0), "1:00.00");
+    assert_eq!(format_time(125.5), "2:05.50");
+    assert_eq!(format_time(92.34), "1:32.34");
+}
+
+#[test]
+fn rounds_to_hundredths_before_splitting() {
+    assert_eq!(format_time(59.999), "1:00.00");
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use standards::{
+    append_counts, read_count_history, write_workbook, AgeGroupCount, AgeGroupSummary, Config,
+    CountHistory, EventCounts, GenderReport, QualifierReport,
+};
+
+use common::FixtureDir;
+
+// Men, age group 12, with the given qualifying swims per event
+fn report(events: &[(&str, usize)]) -> QualifierReport {
+    QualifierReport {
+        genders: vec![GenderReport {
+            gender: "Men".to_string(),
+            course: None,
+            age_groups: vec!["12".to_string()],
+            age_labels: vec!["11-12".to_string()],
+            events: events.iter()
+                .map(|&(event, swims)| EventCounts {
+                    event: event.to_string(),
+                    counts: vec![AgeGroupCount {
+                        age_group: "12".to_string(),
+                        qualifying_swims: swims,
+                        qualified_athletes: swims,
+                    }],
+                    no_standard: false,
+                })
+                .collect(),
+            relays: Vec::new(),
+            stroke_totals: Vec::new(),
+            summary: vec![AgeGroupSummary {
+                age_group: "12".to_string(),
+                total_athletes: 5,
+                unique_qualifiers: 2,
+                qualified_pct: Some(40.0),
+            }],
+            cut_levels: Vec::new(),
+        }],
+    }
+}
+
+// (event, values) for each history row
+fn values(history: &CountHistory) -> Vec<(String, Vec<Option<f64>>)> {
+    history.rows.iter().map(|row| (row.event.clone(), row.values.clone())).collect()
+}
+
+#[test]
+fn rows_are_matched_on_their_key() {
+    let mut history = CountHistory::default();
+    append_counts(&mut history, "2025-01-10", &report(&[("50Fr", 2), ("100Bk", 1)]));
+    append_counts(&mut history, "2025-01-17", &report(&[("50Fr", 3), ("200IM", 1)]));
+
+    assert_eq!(history.columns, ["2025-01-10", "2025-01-17"]);
+    assert_eq!(values(&history), vec![
+        ("50Fr".to_string(), vec![Some(2.0), Some(3.0)]),
+        ("100Bk".to_string(), vec![Some(1.0), None]),
+        ("200IM".to_string(), vec![None, Some(1.0)]),
+    ]);
+    assert!(history.rows.iter().all(|row| row.gender == "Men" && row.age_group == "11-12"));
+}
+
+#[test]
+fn same_column_is_replaced() {
+    let mut history = CountHistory::default();
+    append_counts(&mut history, "2025-01-10", &report(&[("50Fr", 2)]));
+    append_counts(&mut history, "2025-01-10", &report(&[("50Fr", 4)]));
+
+    assert_eq!(history.columns, ["2025-01-10"]);
+    assert_eq!(values(&history), vec![("50Fr".to_string(), vec![Some(4.0)])]);
+}
+
+#[test]
+fn history_survives_a_rewrite_of_the_output() {
+    let dir = FixtureDir::new("history");
+    let config = Config { output: dir.0.join("qualifier_counts.xlsx"), append: true, ..Config::default() };
+
+    // No output yet: the history starts empty
+    let mut history = read_count_history(&config.output).unwrap();
+    assert_eq!(history, CountHistory::default());
+
+    let first = report(&[("50Fr", 2)]);
+    append_counts(&mut history, "2025-01-10", &first);
+    write_workbook(&config, &first, &[], &[], &[], &[], &[], &[], &[], &[], &[], Some(&history)).unwrap();
+
+    let second = report(&[("50Fr", 3), ("100Bk", 1)]);
+    let mut history = read_count_history(&config.output).unwrap();
+    append_counts(&mut history, "2025-01-17", &second);
+    write_workbook(&config, &second, &[], &[], &[], &[], &[], &[], &[], &[], &[], Some(&history)).unwrap();
+
+    let history = read_count_history(&config.output).unwrap();
+    assert_eq!(history.columns, ["2025-01-10", "2025-01-17"]);
+    assert_eq!(values(&history), vec![
+        ("50Fr".to_string(), vec![Some(2.0), Some(3.0)]),
+        ("100Bk".to_string(), vec![None, Some(1.0)]),
+    ]);
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use standards::{long_rows, CutLevelCounts, GenderReport, QualifierReport};
+
+use common::{event, summary};
+
+const AGES: [&str; 2] = ["10", "12"];
+
+#[test]
+fn one_row_per_count_with_summary_and_cut_levels() {
+    let report = QualifierReport {
+        genders: vec![GenderReport {
+            gender: "Men".to_string(),
+            course: None,
+            age_groups: vec!["10".to_string(), "12".to_string()],
+            age_labels: vec!["10".to_string(), "11-12".to_string()],
+            events: vec![event("50Fr", &AGES, &[(2, 1), (0, 0)])],
+            relays: Vec::new(),
+            stroke_totals: Vec::new(),
+            summary: vec![summary("10", 4, 1), summary("12", 0, 0)],
+            cut_levels: vec![CutLevelCounts {
+                cut_level: "AA".to_string(),
+                events: vec![event("50Fr", &AGES, &[(1, 1), (0, 0)])],
+                relays: Vec::new(),
+            }],
+        }],
+    };
+
+    let rows: Vec<_> = long_rows(&report).into_iter()
+        .map(|r| (r.age_group, r.event, r.cut_level, r.metric, r.value))
+        .collect();
+    let row = |age: &str, event: Option<&str>, level: Option<&str>, metric, value| {
+        (age.to_string(), event.map(str::to_string), level.map(str::to_string), metric, value)
+    };
+    assert_eq!(rows, vec![
+        row("10", Some("50Fr"), None, "Qualifying Swims", 2.0),
+        row("10", Some("50Fr"), None, "Qualified Athletes", 1.0),
+        row("11-12", Some("50Fr"), None, "Qualifying Swims", 0.0),
+        row("11-12", Some("50Fr"), None, "Qualified Athletes", 0.0),
+        // No "Qualified %" for an age group without athletes
+        row("10", None, None, "Total Unique Athletes", 4.0),
+        row("10", None, None, "Unique Qualifiers", 1.0),
+        row("10", None, None, "Qualified %", 25.0),
+        row("11-12", None, None, "Total Unique Athletes", 0.0),
+        row("11-12", None, None, "Unique Qualifiers", 0.0),
+        row("10", Some("50Fr"), Some("AA"), "Qualifying Swims", 1.0),
+        row("10", Some("50Fr"), Some("AA"), "Qualified Athletes", 1.0),
+        row("11-12", Some("50Fr"), Some("AA"), "Qualifying Swims", 0.0),
+        row("11-12", Some("50Fr"), Some("AA"), "Qualified Athletes", 0.0),
+    ]);
+}
+
+#[test]
+fn relays_have_their_own_metrics() {
+    let report = QualifierReport {
+        genders: vec![GenderReport {
+            gender: "Women".to_string(),
+            course: None,
+            age_groups: vec!["10".to_string(), "12".to_string()],
+            age_labels: vec!["10".to_string(), "12".to_string()],
+            events: vec![event("50Fr", &AGES, &[(1, 1), (0, 0)])],
+            relays: vec![event("4x50FrR", &AGES, &[(0, 0), (2, 1)])],
+            stroke_totals: Vec::new(),
+            summary: vec![summary("10", 1, 1), summary("12", 0, 0)],
+            cut_levels: Vec::new(),
+        }],
+    };
+
+    let rows: Vec<_> = long_rows(&report).into_iter()
+        .filter(|r| r.event.is_some())
+        .map(|r| (r.age_group, r.event.unwrap_or_default(), r.metric, r.value))
+        .collect();
+    let row = |age: &str, event: &str, metric, value| (age.to_string(), event.to_string(), metric, value);
+    assert_eq!(rows, vec![
+        row("10", "50Fr", "Qualifying Swims", 1.0),
+        row("10", "50Fr", "Qualified Athletes", 1.0),
+        row("12", "50Fr", "Qualifying Swims", 0.0),
+        row("12", "50Fr", "Qualified Athletes", 0.0),
+        row("10", "4x50FrR", "Qualifying Relays", 0.0),
+        row("10", "4x50FrR", "Qualified Relay Teams", 0.0),
+        row("12", "4x50FrR", "Qualifying Relays", 2.0),
+        row("12", "4x50FrR", "Qualified Relay Teams", 1.0),
+    ]);
+}
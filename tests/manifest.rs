@@ -0,0 +1,49 @@
+mod common;
+
+use std::path::Path;
+
+use standards::{manifest_path, write_manifest, InputFile, Manifest, ManifestTotals, VERSION};
+
+use common::FixtureDir;
+
+#[test]
+fn manifest_sits_next_to_the_output() {
+    assert_eq!(manifest_path(Path::new("out/qualifier_counts.xlsx")), Path::new("out/manifest.json"));
+    assert_eq!(manifest_path(Path::new("qualifier_counts.json")), Path::new("manifest.json"));
+}
+
+#[test]
+fn manifest_records_inputs_and_totals() {
+    let dir = FixtureDir::new("manifest");
+    let standards = dir.0.join("standards.xlsx");
+    let meet = dir.0.join("CAN-MBSK_Test_SCY_Men_12.csv");
+    std::fs::write(&standards, b"not a real workbook").unwrap();
+    std::fs::write(&meet, b"Name,Time\n").unwrap();
+
+    let manifest = Manifest {
+        version: VERSION,
+        generated: chrono::Utc::now(),
+        args: vec!["--tolerance".to_string(), "0.05".to_string()],
+        config: None,
+        standards: InputFile::read(&standards).unwrap(),
+        previous_standards: None,
+        standards_course: "SCY".to_string(),
+        tolerance: 0.05,
+        meet_files: vec![InputFile::read(&meet).unwrap()],
+        outputs: vec![dir.0.join("qualifier_counts.xlsx")],
+        totals: ManifestTotals { meet_files: 1, results: 3, qualifiers: 2, warnings: 0 },
+    };
+    let path = manifest_path(&dir.0.join("qualifier_counts.xlsx"));
+    write_manifest(&path, &manifest).unwrap();
+
+    let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(json["version"], VERSION);
+    assert_eq!(json["args"], serde_json::json!(["--tolerance", "0.05"]));
+    assert_eq!(json["standards"]["size"], 19);
+    assert_eq!(json["meet_files"][0]["size"], 10);
+    assert!(json["meet_files"][0]["modified"].is_string());
+    assert_eq!(json["totals"]["qualifiers"], 2);
+    // Unused optional inputs are left out rather than written as null
+    assert!(json.get("config").is_none());
+    assert!(json.get("previous_standards").is_none());
+}
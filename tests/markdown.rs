@@ -0,0 +1,165 @@
+mod common;
+
+use standards::{combine_ages, markdown_report, CountMode, EventCounts, GenderReport, QualifierReport};
+
+use common::{event, summary};
+
+const AGES: [&str; 2] = ["10", "11"];
+
+// Men, ages 10 and 11, two events
+fn men_report() -> QualifierReport {
+    QualifierReport {
+        genders: vec![GenderReport {
+            gender: "Men".to_string(),
+            course: None,
+            age_groups: vec!["10".to_string(), "11".to_string()],
+            age_labels: vec!["10".to_string(), "11".to_string()],
+            events: vec![
+                event("50Fr", &AGES, &[(2, 2), (1, 1)]),
+                event("100Bk", &AGES, &[(0, 0), (3, 3)]),
+            ],
+            relays: Vec::new(),
+            stroke_totals: Vec::new(),
+            summary: vec![summary("10", 4, 1), summary("11", 0, 0)],
+            cut_levels: Vec::new(),
+        }],
+    }
+}
+
+#[test]
+fn one_table_per_gender_with_totals_and_summary() {
+    let report = men_report();
+
+    let expected = "\
+## Mens
+
+| Event (Qualifying Swims) | 10 | 11 | Total |
+| :--- | ---: | ---: | ---: |
+| 50Fr | 2 | 1 | 3 |
+| 100Bk | 0 | 3 | 3 |
+| Total | 2 | 4 | 6 |
+| Total Unique Athletes | 4 | 0 |  |
+| Unique Qualifiers | 1 | 0 |  |
+| Qualified % | 25.0% |  |  |
+";
+    assert_eq!(markdown_report(&report, CountMode::Swims), expected);
+}
+
+#[test]
+fn athletes_mode_shows_qualified_athletes() {
+    let mut report = men_report();
+    // Two swims by one athlete in the 10 50Fr
+    report.genders[0].events[0] = event("50Fr", &AGES, &[(2, 1), (1, 1)]);
+
+    let expected = "\
+## Mens
+
+| Event (Qualified Athletes) | 10 | 11 | Total |
+| :--- | ---: | ---: | ---: |
+| 50Fr | 1 | 1 | 2 |
+| 100Bk | 0 | 3 | 3 |
+| Total | 1 | 4 | 5 |
+| Total Unique Athletes | 4 | 0 |  |
+| Unique Qualifiers | 1 | 0 |  |
+| Qualified % | 25.0% |  |  |
+";
+    assert_eq!(markdown_report(&report, CountMode::Athletes), expected);
+}
+
+#[test]
+fn relays_get_a_separate_table() {
+    let report = QualifierReport {
+        genders: vec![GenderReport {
+            gender: "Women".to_string(),
+            course: None,
+            age_groups: vec!["10".to_string(), "11".to_string()],
+            age_labels: vec!["10".to_string(), "11".to_string()],
+            events: vec![event("50Fr", &AGES, &[(1, 1), (2, 2)])],
+            relays: vec![event("4x50FrR", &AGES, &[(0, 0), (1, 1)])],
+            stroke_totals: Vec::new(),
+            summary: vec![summary("10", 2, 1), summary("11", 2, 2)],
+            cut_levels: Vec::new(),
+        }],
+    };
+
+    // The relay isn't in the first table's totals
+    let expected = "\
+## Womens
+
+| Event (Qualifying Swims) | 10 | 11 | Total |
+| :--- | ---: | ---: | ---: |
+| 50Fr | 1 | 2 | 3 |
+| Total | 1 | 2 | 3 |
+| Total Unique Athletes | 2 | 2 |  |
+| Unique Qualifiers | 1 | 2 |  |
+| Qualified % | 50.0% | 100.0% |  |
+
+| Relay (Qualifying Relays) | 10 | 11 | Total |
+| :--- | ---: | ---: | ---: |
+| 4x50FrR | 0 | 1 | 1 |
+| Total | 0 | 1 | 1 |
+";
+    assert_eq!(markdown_report(&report, CountMode::Swims), expected);
+}
+
+#[test]
+fn all_ages_column_replaces_the_total_column() {
+    let mut report = men_report();
+    combine_ages(&mut report, true);
+
+    let expected = "\
+## Mens
+
+| Event (Qualifying Swims) | 10 | 11 | All Ages |
+| :--- | ---: | ---: | ---: |
+| 50Fr | 2 | 1 | 3 |
+| 100Bk | 0 | 3 | 3 |
+| Total | 2 | 4 | 6 |
+| Total Unique Athletes | 4 | 0 | 4 |
+| Unique Qualifiers | 1 | 0 | 1 |
+| Qualified % | 25.0% |  | 25.0% |
+";
+    assert_eq!(markdown_report(&report, CountMode::Swims), expected);
+}
+
+#[test]
+fn combine_ages_only_drops_the_age_columns() {
+    let mut report = men_report();
+    combine_ages(&mut report, false);
+    assert_eq!(report.genders[0].age_groups, ["All Ages"]);
+
+    let expected = "\
+## Mens
+
+| Event (Qualifying Swims) | All Ages |
+| :--- | ---: |
+| 50Fr | 3 |
+| 100Bk | 3 |
+| Total | 6 |
+| Total Unique Athletes | 4 |
+| Unique Qualifiers | 1 |
+| Qualified % | 25.0% |
+";
+    assert_eq!(markdown_report(&report, CountMode::Swims), expected);
+}
+
+#[test]
+fn standard_less_events_are_marked() {
+    let mut report = men_report();
+    report.genders[0].events.push(EventCounts { no_standard: true, ..event("100Bu", &AGES, &[(0, 0), (0, 0)]) });
+
+    let expected = "\
+## Mens
+
+| Event (Qualifying Swims) | 10 | 11 | Total |
+| :--- | ---: | ---: | ---: |
+| 50Fr | 2 | 1 | 3 |
+| 100Bk | 0 | 3 | 3 |
+| 100Bu | no standard |  |  |
+| Total | 2 | 4 | 6 |
+| Total Unique Athletes | 4 | 0 |  |
+| Unique Qualifiers | 1 | 0 |  |
+| Qualified % | 25.0% |  |  |
+";
+    assert_eq!(markdown_report(&report, CountMode::Swims), expected);
+}
@@ -0,0 +1,155 @@
+use chrono::NaiveDate;
+use std::collections::HashMap;
+use std::path::Path;
+
+use standards::{
+    meet_date, parse_meet_csv, results_by_course, to_standards_course, unmapped_sexes,
+    AnalysisError, Config, Course, EventStandards, MeetResult, Warning, WarningCategory,
+};
+
+// Writes a one-swim CSV meet file with the given name to a temp folder and
+// parses it, removing the file afterwards
+fn parse_csv_named(filename: &str) -> Result<Vec<MeetResult>, AnalysisError> {
+    parse_csv_with(filename, &Config::default())
+}
+
+fn parse_csv_with(filename: &str, config: &Config) -> Result<Vec<MeetResult>, AnalysisError> {
+    parse_csv_contents(filename, "Name,Time\nAnn Able,30.50\n", config, &mut Vec::new())
+}
+
+fn parse_csv_contents(
+    filename: &str,
+    contents: &str,
+    config: &Config,
+    warnings: &mut Vec<Warning>,
+) -> Result<Vec<MeetResult>, AnalysisError> {
+    let dir = std::env::temp_dir().join(format!("standards-filename-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(filename);
+    std::fs::write(&path, contents).unwrap();
+
+    let results = parse_meet_csv(&path, config, warnings);
+    let _ = std::fs::remove_file(&path);
+    results
+}
+
+#[test]
+fn age_range_uses_upper_age() {
+    let results = parse_csv_named("CAN-MBSK_2024_SCY_Men_13-14_50 Free.csv").unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].age(), "14");
+    assert_eq!(results[0].age_min(), "13");
+    assert_eq!(results[0].event(), "50Fr");
+}
+
+#[test]
+fn single_age_without_dash() {
+    let results = parse_csv_named("CAN-MBSK_2024_SCY_Men_12_50 Free.csv").unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].age(), "12");
+    assert_eq!(results[0].age_min(), "12");
+}
+
+#[test]
+fn non_numeric_age_is_rejected() {
+    let err = parse_csv_named("CAN-MBSK_2024_SCY_Men_Open_50 Free.csv").unwrap_err();
+    assert!(matches!(err, AnalysisError::InvalidAgeRange(ref age) if age == "Open"));
+}
+
+#[test]
+fn meet_date_from_filename() {
+    let date = |name: &str| meet_date(Path::new(name));
+    assert_eq!(date("CAN-MBSK_2024-03-15_SCY_Men_11-12.xlsx"), NaiveDate::from_ymd_opt(2024, 3, 15));
+    assert_eq!(date("CAN-MBSK_20240315_SCY_Men_11-12.xlsx"), NaiveDate::from_ymd_opt(2024, 3, 15));
+    assert_eq!(date("CAN-MBSK_2024_SCY_Men_11-12.xlsx"), None);
+    assert_eq!(date("CAN-MBSK_2024-02-30_SCY_Men_11-12.xlsx"), None);
+}
+
+#[test]
+fn results_carry_meet_and_date() {
+    let results = parse_csv_named("CAN-MBSK_2024-03-15_SCY_Men_12_50 Free.csv").unwrap();
+    assert_eq!(results[0].meet(), "2024-03-15");
+    assert_eq!(results[0].date, NaiveDate::from_ymd_opt(2024, 3, 15));
+}
+
+#[test]
+fn results_are_grouped_by_course_code() {
+    let mut results = parse_csv_named("CAN-MBSK_2024_SCY_Men_12_50 Free.csv").unwrap();
+    results.extend(parse_csv_named("CAN-MBSK_2024_lcm_Men_12_50 Free.csv").unwrap());
+    results.extend(parse_csv_named("CAN-MBSK_2025_SCY_Men_12_50 Free.csv").unwrap());
+
+    let courses = results_by_course(&results);
+    let codes: Vec<&str> = courses.keys().map(String::as_str).collect();
+    assert_eq!(codes, ["LCM", "SCY"]);
+    assert_eq!(courses["LCM"].len(), 1);
+    assert_eq!(courses["SCY"].len(), 2);
+}
+
+#[test]
+fn converted_results_keep_the_course_they_were_swum_in() {
+    let config = Config::default();
+    let contents = "Name,Time\nAnn Able,4:30.00\n";
+    let mut results =
+        parse_csv_contents("CAN-MBSK_2024_LCM_Men_12_400 Free.csv", contents, &config, &mut Vec::new()).unwrap();
+    results.extend(parse_csv_named("CAN-MBSK_2024_SCY_Men_12_50 Free.csv").unwrap());
+
+    to_standards_course(&mut results, Course::Scy);
+    // The 400 LCM is compared as a 500 SCY; the SCY swim is unchanged
+    assert_eq!(results[0].event(), "500Fr");
+    assert!((results[0].time - 270.0 / 0.8925).abs() < 1e-9);
+    assert_eq!(results[1].event(), "50Fr");
+    assert_eq!(results[1].time, 30.5);
+
+    // The per-course count sheets still split them
+    let codes: Vec<String> = results_by_course(&results).into_keys().collect();
+    assert_eq!(codes, ["LCM", "SCY"]);
+}
+
+#[test]
+fn sex_codes_map_to_standards_genders() {
+    let sex = |filename: &str| parse_csv_named(filename).unwrap()[0].sex().to_string();
+    assert_eq!(sex("CAN-MBSK_2024_SCY_B_12_50 Free.csv"), "Men");
+    assert_eq!(sex("CAN-MBSK_2024_SCY_m_12_50 Free.csv"), "Men");
+    assert_eq!(sex("CAN-MBSK_2024_SCY_G_12_50 Free.csv"), "Women");
+    assert_eq!(sex("CAN-MBSK_2024_SCY_Women_12_50 Free.csv"), "Women");
+    // Not listed: kept as written
+    assert_eq!(sex("CAN-MBSK_2024_SCY_Q_12_50 Free.csv"), "Q");
+
+    let config = Config {
+        sex_codes: HashMap::from([("H".to_string(), "Men".to_string())]),
+        ..Config::default()
+    };
+    let results = parse_csv_with("CAN-MBSK_2024_SCY_H_12_50 Free.csv", &config).unwrap();
+    assert_eq!(results[0].sex(), "Men");
+}
+
+#[test]
+fn unmapped_sexes_are_counted() {
+    let mut results = parse_csv_named("CAN-MBSK_2024_SCY_B_12_50 Free.csv").unwrap();
+    results.extend(parse_csv_named("CAN-MBSK_2024_SCY_Q_12_50 Free.csv").unwrap());
+    results.extend(parse_csv_named("CAN-MBSK_2024_SCY_Q_11_50 Free.csv").unwrap());
+
+    let standards: HashMap<String, EventStandards> = HashMap::from([
+        ("Men".to_string(), EventStandards::new()),
+        ("Women".to_string(), EventStandards::new()),
+    ]);
+    let unmapped = unmapped_sexes(&results, &standards);
+    assert_eq!(unmapped.into_iter().collect::<Vec<_>>(), [("Q".to_string(), 2)]);
+}
+
+#[test]
+fn csv_header_after_empty_rows_is_found() {
+    let contents = ",,\n,,\nName,Time,Event\nAnn Able,30.50,50 Free\nBo Baker,fast,50 Free\nCy Carter,1:05.00,100 Back\n";
+    let mut warnings = Vec::new();
+    let results = parse_csv_contents("CAN-MBSK_2024_SCY_Men_12.csv", contents, &Config::default(), &mut warnings)
+        .unwrap();
+
+    let swims: Vec<(&str, &str)> = results.iter().map(|r| (r.name.as_str(), r.event())).collect();
+    assert_eq!(swims, [("Ann Able", "50Fr"), ("Cy Carter", "100Bk")]);
+    // Rows are numbered as in the file
+    let unparseable: Vec<Option<usize>> = warnings.iter()
+        .filter(|w| w.category == WarningCategory::UnparseableTime)
+        .map(|w| w.row)
+        .collect();
+    assert_eq!(unparseable, [Some(5)]);
+}
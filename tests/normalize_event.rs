@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+use standards::{
+    default_stroke_names, event_parts, normalize_any_event, normalize_event_name,
+    normalize_event_name_with, parse_event_components, sort_events, EventComponents, EventParts,
+    EventSort,
+};
+
+fn event(name: &str) -> Option<String> {
+    normalize_event_name(name)
+}
+
+#[test]
+fn full_stroke_names() {
+    assert_eq!(event("50 Butterfly").as_deref(), Some("50Bu"));
+    assert_eq!(event("100 Backstroke").as_deref(), Some("100Bk"));
+    assert_eq!(event("200 Breaststroke").as_deref(), Some("200Br"));
+    assert_eq!(event("400 Freestyle").as_deref(), Some("400Fr"));
+}
+
+#[test]
+fn medley_spellings() {
+    assert_eq!(event("200 IM").as_deref(), Some("200Me"));
+    assert_eq!(event("200 I.M.").as_deref(), Some("200Me"));
+    assert_eq!(event("400 Individual Medley").as_deref(), Some("400Me"));
+    assert_eq!(event("200 M.E.").as_deref(), Some("200Me"));
+}
+
+#[test]
+fn short_forms_and_case() {
+    assert_eq!(event("50 FLY").as_deref(), Some("50Bu"));
+    assert_eq!(event("50 FL").as_deref(), Some("50Bu"));
+    assert_eq!(event("100m back").as_deref(), Some("100Bk"));
+}
+
+#[test]
+fn short_stroke_names() {
+    assert_eq!(event("50m Free").as_deref(), Some("50Fr"));
+    assert_eq!(event("100 Fly").as_deref(), Some("100Bu"));
+    assert_eq!(event("50 Back").as_deref(), Some("50Bk"));
+    assert_eq!(event("100 Breast").as_deref(), Some("100Br"));
+}
+
+#[test]
+fn empty_name_is_not_an_event() {
+    assert_eq!(event(""), None);
+    assert_eq!(event("   "), None);
+}
+
+// Every lowercase "m" used to be stripped, not just the distance unit, so
+// lowercase names broke ("100m Swim" -> "100Swi"); only the unit goes now
+#[test]
+fn lowercase_distance_unit_is_dropped() {
+    assert_eq!(event("50m freestyle").as_deref(), Some("50Fr"));
+    assert_eq!(event("100m Swim").as_deref(), Some("100Swim"));
+    assert_eq!(event("50 m Free").as_deref(), Some("50Fr"));
+    assert_eq!(event("200m I.M.").as_deref(), Some("200Me"));
+    assert_eq!(event("400m IM").as_deref(), Some("400Me"));
+}
+
+#[test]
+fn names_inside_words_are_kept() {
+    // "im" in "Swim" is not the IM abbreviation
+    assert_eq!(event("50 Swim").as_deref(), Some("50Swim"));
+}
+
+#[test]
+fn relays_use_the_same_names() {
+    assert_eq!(normalize_any_event("4x50 Medley Relay"), Some(("4x50MeR".to_string(), true)));
+    assert_eq!(normalize_any_event("4x100 Freestyle Relay"), Some(("4x100FrR".to_string(), true)));
+}
+
+#[test]
+fn relays_named_by_total_distance_are_split_over_four_legs() {
+    assert_eq!(normalize_any_event("200 Free Relay"), Some(("4x50FrR".to_string(), true)));
+    assert_eq!(normalize_any_event("400m Medley Relay"), Some(("4x100MeR".to_string(), true)));
+    assert_eq!(normalize_any_event("4x50 Free Relay"), normalize_any_event("200 Free Relay"));
+    // Without "Relay" it's an individual event
+    assert_eq!(normalize_any_event("200 Free"), Some(("200Fr".to_string(), false)));
+}
+
+#[test]
+fn custom_stroke_names() {
+    let mut strokes = default_stroke_names();
+    strokes.insert("Papillon".to_string(), "Bu".to_string());
+    assert_eq!(normalize_event_name_with("50 Papillon", &strokes).as_deref(), Some("50Bu"));
+
+    let only_free: HashMap<String, String> =
+        [("Free".to_string(), "Fr".to_string())].into_iter().collect();
+    assert_eq!(normalize_event_name_with("50 Fly", &only_free).as_deref(), Some("50Fly"));
+}
+
+fn sorted(events: &[&str], sort: EventSort) -> Vec<String> {
+    let mut events: Vec<String> = events.iter().map(|e| e.to_string()).collect();
+    sort_events(&mut events, sort);
+    events
+}
+
+#[test]
+fn event_parts_split_relays() {
+    let parts = |legs, distance, stroke: &str, is_relay| {
+        EventParts { legs, distance, stroke: stroke.to_string(), is_relay }
+    };
+    assert_eq!(event_parts("50Fr"), Some(parts(1, 50, "Fr", false)));
+    assert_eq!(event_parts("4x50MeR"), Some(parts(4, 50, "Me", true)));
+    assert_eq!(event_parts("Summary"), None);
+}
+
+#[test]
+fn event_components_of_individual_events() {
+    let components = |distance, stroke: &str| Some(EventComponents { distance, stroke: stroke.to_string() });
+    assert_eq!(parse_event_components("50Fr"), components(50, "Fr"));
+    assert_eq!(parse_event_components("200Me"), components(200, "Me"));
+    assert_eq!(parse_event_components("1500Fr"), components(1500, "Fr"));
+
+    // Relays, with or without the trailing R, and names that aren't events
+    assert_eq!(parse_event_components("4x50Fr"), None);
+    assert_eq!(parse_event_components("4x50FrR"), None);
+    assert_eq!(parse_event_components("Summary"), None);
+    assert_eq!(parse_event_components("50"), None);
+    assert_eq!(parse_event_components(""), None);
+}
+
+#[test]
+fn event_sort_orders() {
+    let events = ["100Bk", "4x50FrR", "200Fr", "50Bu", "50Fr", "100Me"];
+    assert_eq!(sorted(&events, EventSort::Standards), events);
+    assert_eq!(sorted(&events, EventSort::Alpha), ["100Bk", "100Me", "200Fr", "4x50FrR", "50Bu", "50Fr"]);
+    assert_eq!(sorted(&events, EventSort::StrokeDistance), ["50Fr", "200Fr", "100Bk", "50Bu", "100Me", "4x50FrR"]);
+}
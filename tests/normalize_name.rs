@@ -0,0 +1,39 @@
+use standards::normalize_name;
+
+#[test]
+fn collapses_whitespace() {
+    assert_eq!(normalize_name("John  Smith", true), "John Smith");
+    assert_eq!(normalize_name("  John \t Smith ", false), "John Smith");
+}
+
+#[test]
+fn reorders_last_first() {
+    assert_eq!(normalize_name("Smith, John", true), "John Smith");
+    assert_eq!(normalize_name("Smith,John", true), "John Smith");
+    assert_eq!(normalize_name("Smith ,  John", true), "John Smith");
+}
+
+#[test]
+fn keeps_order_when_disabled() {
+    assert_eq!(normalize_name("Smith, John", false), "Smith, John");
+}
+
+#[test]
+fn leaves_incomplete_names_alone() {
+    assert_eq!(normalize_name("Smith,", true), "Smith,");
+    assert_eq!(normalize_name("John Smith", true), "John Smith");
+}
+
+#[test]
+fn drops_middle_initials() {
+    assert_eq!(normalize_name("Smith, John A.", true), "John Smith");
+    assert_eq!(normalize_name("John A. Smith", true), "John Smith");
+    assert_eq!(normalize_name("Smith, J. Robert", true), "J. Robert Smith");
+}
+
+#[test]
+fn keeps_suffixes_last() {
+    assert_eq!(normalize_name("Smith Jr., John", true), "John Smith Jr");
+    assert_eq!(normalize_name("Smith, John A., Jr.", true), "John Smith Jr");
+    assert_eq!(normalize_name("John Smith III", true), "John Smith III");
+}
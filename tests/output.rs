@@ -0,0 +1,39 @@
+mod common;
+
+use std::path::Path;
+
+use standards::{temp_path, write_atomic, AnalysisError};
+
+use common::FixtureDir;
+
+#[test]
+fn temp_file_sits_next_to_the_output() {
+    assert_eq!(temp_path(Path::new("out/qualifier_counts.xlsx")), Path::new("out/qualifier_counts.xlsx.tmp"));
+}
+
+#[test]
+fn successful_write_replaces_the_output() {
+    let dir = FixtureDir::new("atomic-ok");
+    let output = dir.0.join("qualifier_counts.md");
+    std::fs::write(&output, "old").unwrap();
+
+    write_atomic(&output, |temp| Ok(std::fs::write(temp, "new")?)).unwrap();
+    assert_eq!(std::fs::read_to_string(&output).unwrap(), "new");
+    assert!(!temp_path(&output).exists());
+}
+
+#[test]
+fn failed_write_keeps_the_previous_output() {
+    let dir = FixtureDir::new("atomic-fail");
+    let output = dir.0.join("qualifier_counts.md");
+    std::fs::write(&output, "old").unwrap();
+
+    // Fails part way through, after the temp file has some content
+    let result = write_atomic(&output, |temp| {
+        std::fs::write(temp, "partial")?;
+        Err(AnalysisError::Io(std::io::Error::other("interrupted")))
+    });
+    assert!(result.is_err());
+    assert_eq!(std::fs::read_to_string(&output).unwrap(), "old");
+    assert!(!temp_path(&output).exists());
+}
@@ -0,0 +1,50 @@
+use std::path::Path;
+
+use standards::{participation, MeetResult};
+
+fn result(name: &str, sex: &str, age_min: &str, age: &str, event: &str, time: f64) -> MeetResult {
+    MeetResult {
+        folder: Path::new("data").into(),
+        meet: "Test".into(),
+        date: None,
+        course: "SCY".into(),
+        sex: sex.into(),
+        age: age.into(),
+        age_min: age_min.into(),
+        event: event.into(),
+        time,
+        split_time: None,
+        round: None,
+        name: name.to_string(),
+        is_relay: false,
+    }
+}
+
+#[test]
+fn participation_counts_athletes_and_swims_without_standards() {
+    let mut relay = result("Team A", "Men", "12", "12", "200FrR", 110.0);
+    relay.is_relay = true;
+    let results = vec![
+        result("Ann Able", "Women", "12", "12", "50Fr", 30.0),
+        result("Ann Able", "Women", "12", "12", "50Fr", 29.5), // Second swim, same athlete
+        result("Bea Baker", "Women", "12", "12", "50Fr", 40.0),
+        result("Bea Baker", "Women", "12", "12", "100Bk", 80.0),
+        result("Cy Carter", "Men", "13", "14", "50Fr", 27.0),
+        result("Di Dunn", "Women", "9", "9", "25Fl", 20.0), // No standard needed
+        result("  ", "Women", "12", "12", "50Fr", 31.0),
+        relay,
+    ];
+
+    let participation = participation(&results);
+    let rows: Vec<(&str, &str, &str, usize, usize)> = participation.iter()
+        .map(|row| (row.sex.as_str(), row.age.as_str(), row.event.as_str(), row.athletes, row.swims))
+        .collect();
+    // By sex, then age; a 13-14 file keeps its range. Relays and blank names
+    // aren't athletes.
+    assert_eq!(rows, [
+        ("Men", "13-14", "50Fr", 1, 1),
+        ("Women", "9", "25Fl", 1, 1),
+        ("Women", "12", "50Fr", 2, 3),
+        ("Women", "12", "100Bk", 1, 1),
+    ]);
+}
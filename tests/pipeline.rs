@@ -0,0 +1,842 @@
+mod common;
+
+use rust_xlsxwriter::{ExcelDateTime, Format, Workbook, XlsxError};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use standards::{
+    age_mismatches, best_times, build_report, count_best_time_qualifiers, count_near_qualifiers,
+    count_qualifiers, count_qualifying_events, count_total_athletes, count_unique_qualifiers,
+    load_time_standards, load_time_standards_labeled, load_time_standards_with,
+    multi_event_qualifiers, normalize_any_event, parse_meet_file, qualifier_details,
+    standards_event_names, status_changes, unmatched_results, with_meet_events, AnalysisError,
+    Config, EventNormalizer, Round, RoundPolicy, StandardsColumns, WarningCategory,
+    DEFAULT_CUT_LEVEL,
+};
+
+use common::FixtureDir;
+
+// Mens tab: events down column A, age groups across row 1
+fn write_standards(path: &Path) -> Result<(), XlsxError> {
+    write_standards_with(path, 31.0, "1:06.00")
+}
+
+// Same layout with different 12 cuts, for comparing two seasons
+fn write_standards_with(path: &Path, free_12: f64, back_12: &str) -> Result<(), XlsxError> {
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("Mens")?;
+
+    let header = ["Event", "10&U", "11", "12"];
+    for (col, label) in header.iter().enumerate() {
+        sheet.write_string(0, col as u16, *label)?;
+    }
+
+    sheet.write_string(1, 0, "50 Free")?;
+    sheet.write_number(1, 1, 35.0)?;
+    sheet.write_number(1, 2, 33.0)?;
+    sheet.write_number(1, 3, free_12)?;
+
+    sheet.write_string(2, 0, "100 Back")?;
+    sheet.write_string(2, 1, "1:20.00")?;
+    sheet.write_string(2, 2, "1:15.00")?;
+    sheet.write_string(2, 3, back_12)?;
+
+    workbook.save(path)
+}
+
+// One sheet per event, name in column E and time in column J like the exports
+fn write_meet(path: &Path, sheets: &[(&str, Vec<(&str, &str)>)]) -> Result<(), XlsxError> {
+    let mut workbook = Workbook::new();
+    for (event, swims) in sheets {
+        let sheet = workbook.add_worksheet();
+        sheet.set_name(*event)?;
+        sheet.write_string(0, 4, "Name")?;
+        sheet.write_string(0, 9, "Time")?;
+        for (i, (name, time)) in swims.iter().enumerate() {
+            let row = (i + 1) as u32;
+            sheet.write_string(row, 4, *name)?;
+            sheet.write_string(row, 9, *time)?;
+        }
+    }
+    workbook.save(path)
+}
+
+fn names(set: Option<&HashSet<String>>) -> Vec<String> {
+    let mut names: Vec<String> = set.into_iter().flatten().cloned().collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn counts_qualifiers_end_to_end() {
+    let dir = FixtureDir::new("pipeline");
+    let standards_path = dir.0.join("timestandards.xlsx");
+    let meet_path = dir.0.join("CAN-MBSK_Test_SCY_Men_11-12.xlsx");
+
+    write_standards(&standards_path).unwrap();
+    write_meet(&meet_path, &[
+        ("50 Free", vec![
+            ("Able, Ann", "30.50"),
+            ("Able, Ann", "30.90"),
+            ("Baker, Bo", "31.50"),
+            ("Carter, Cy", "DQ"),
+        ]),
+        ("100 Back", vec![
+            ("Baker, Bo", "1:05.00"),
+            ("Carter, Cy", "1:10.00"),
+        ]),
+    ]).unwrap();
+
+    let config = Config::default();
+    let men_only = ["Men".to_string()];
+    let (standards, event_orders) =
+        load_time_standards(&standards_path, &config.gender_tabs, &config.stroke_names, &men_only).unwrap();
+    assert_eq!(event_orders["Men"], vec!["50Fr", "100Bk"]);
+    assert_eq!(standards["Men"]["50Fr"]["10"][DEFAULT_CUT_LEVEL], 35.0);
+    assert_eq!(standards["Men"]["100Bk"]["12"][DEFAULT_CUT_LEVEL], 66.0);
+
+    // Age comes from the upper end of the filename's 11-12 range
+    let results = parse_meet_file(&meet_path, &config, &mut Vec::new()).unwrap();
+    assert_eq!(results.len(), 5);
+    assert!(results.iter().all(|r| r.sex() == "Men" && r.age() == "12" && r.course() == "SCY"));
+
+    let key = |event: &str| ("Men".to_string(), "12".to_string(), event.to_string());
+
+    let swims = count_qualifiers(&results, &standards, 0.0);
+    assert_eq!(swims.get(&key("50Fr")), Some(&2));
+    assert_eq!(swims.get(&key("100Bk")), Some(&1));
+
+    let athletes = count_best_time_qualifiers(&results, &standards, 0.0);
+    assert_eq!(athletes.get(&key("50Fr")), Some(&1));
+    assert_eq!(athletes.get(&key("100Bk")), Some(&1));
+
+    let group = ("Men".to_string(), "12".to_string());
+    let unique = count_unique_qualifiers(&results, &standards, 0.0);
+    // "Last, First" names are reordered when results are read
+    assert_eq!(names(unique.get(&group)), vec!["Ann Able", "Bo Baker"]);
+
+    let total = count_total_athletes(&results, &standards);
+    assert_eq!(names(total.get(&group)), vec!["Ann Able", "Bo Baker", "Cy Carter"]);
+}
+
+#[test]
+fn duplicate_rows_are_dropped_with_dedup() {
+    let dir = FixtureDir::new("dedup");
+    let standards_path = dir.0.join("timestandards.xlsx");
+    let meet_path = dir.0.join("CAN-MBSK_Test_SCY_Men_11-12.xlsx");
+
+    write_standards(&standards_path).unwrap();
+    write_meet(&meet_path, &[
+        ("50 Free", vec![
+            ("Able, Ann", "30.50"),
+            ("Able, Ann", "30.50"), // exported twice
+            ("Able, Ann", "30.90"),
+        ]),
+    ]).unwrap();
+
+    let mut config = Config::default();
+    let men_only = ["Men".to_string()];
+    let (standards, _) =
+        load_time_standards(&standards_path, &config.gender_tabs, &config.stroke_names, &men_only).unwrap();
+    let key = ("Men".to_string(), "12".to_string(), "50Fr".to_string());
+
+    // Without --dedup the repeat is only reported
+    let mut warnings = Vec::new();
+    let results = parse_meet_file(&meet_path, &config, &mut warnings).unwrap();
+    assert_eq!(results.len(), 3);
+    assert_eq!(count_qualifiers(&results, &standards, 0.0).get(&key), Some(&3));
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].category, WarningCategory::DuplicateRows);
+    assert_eq!(warnings[0].file.as_deref(), Some("CAN-MBSK_Test_SCY_Men_11-12.xlsx"));
+    assert_eq!(warnings[0].sheet.as_deref(), Some("50 Free"));
+
+    config.dedup = true;
+    let results = parse_meet_file(&meet_path, &config, &mut Vec::new()).unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(count_qualifiers(&results, &standards, 0.0).get(&key), Some(&2));
+}
+
+#[test]
+fn missing_gender_tab_fails_validation() {
+    let dir = FixtureDir::new("validate");
+    let standards_path = dir.0.join("timestandards.xlsx");
+    write_standards(&standards_path).unwrap();
+
+    // The fixture only has a Mens tab; the defaults also require Women
+    let config = Config::default();
+    let err = load_time_standards(&standards_path, &config.gender_tabs, &config.stroke_names,
+                                  &config.required_genders).unwrap_err();
+    match err {
+        AnalysisError::InvalidStandards { problems, .. } => {
+            assert_eq!(problems.len(), 1);
+            assert!(problems[0].starts_with("no tab for Women"), "{}", problems[0]);
+        }
+        other => panic!("expected InvalidStandards, got {}", other),
+    }
+}
+
+#[test]
+fn gender_tabs_match_ignoring_case_and_trailing_spaces() {
+    let dir = FixtureDir::new("tab-case");
+    let standards_path = dir.0.join("timestandards.xlsx");
+
+    let mut workbook = Workbook::new();
+    for tab in ["mens", "WOMENS "] {
+        let sheet = workbook.add_worksheet();
+        sheet.set_name(tab).unwrap();
+        sheet.write_string(0, 0, "Event").unwrap();
+        sheet.write_string(0, 1, "12").unwrap();
+        sheet.write_string(1, 0, "50 Free").unwrap();
+        sheet.write_number(1, 1, 31.0).unwrap();
+    }
+    workbook.save(&standards_path).unwrap();
+
+    // The defaults map "Mens" and "Womens" and require both genders
+    let config = Config::default();
+    let (standards, _) = load_time_standards(&standards_path, &config.gender_tabs, &config.stroke_names,
+                                             &config.required_genders).unwrap();
+    let mut genders: Vec<&str> = standards.keys().map(|gender| gender.as_str()).collect();
+    genders.sort();
+    assert_eq!(genders, ["Men", "Women"]);
+    assert_eq!(standards["Women"]["50Fr"]["12"][DEFAULT_CUT_LEVEL], 31.0);
+}
+
+#[test]
+fn unreadable_times_are_recorded_with_their_row() {
+    let dir = FixtureDir::new("warnings");
+    let meet_path = dir.0.join("CAN-MBSK_Test_SCY_Men_11-12.xlsx");
+    write_meet(&meet_path, &[
+        ("50 Free", vec![("Able, Ann", "30.50"), ("Baker, Bo", "fast")]),
+    ]).unwrap();
+
+    let mut warnings = Vec::new();
+    let results = parse_meet_file(&meet_path, &Config::default(), &mut warnings).unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].category, WarningCategory::UnparseableTime);
+    // Header in row 1, so Bo Baker's swim is spreadsheet row 3
+    assert_eq!(warnings[0].row, Some(3));
+    assert_eq!(warnings[0].to_string(), "CAN-MBSK_Test_SCY_Men_11-12.xlsx sheet '50 Free' row 3: time 'fast' could not be read");
+}
+
+#[test]
+fn swims_without_an_exact_age_standard_are_listed() {
+    let dir = FixtureDir::new("mismatch");
+    let standards_path = dir.0.join("timestandards.xlsx");
+    // Standards stop at 12, so 13-14 swimmers match the 12 group
+    let meet_path = dir.0.join("CAN-MBSK_Test_SCY_Men_13-14.xlsx");
+
+    write_standards(&standards_path).unwrap();
+    write_meet(&meet_path, &[
+        ("50 Free", vec![("Able, Ann", "30.50"), ("Baker, Bo", "32.00")]),
+    ]).unwrap();
+
+    let config = Config::default();
+    let men_only = ["Men".to_string()];
+    let (standards, _) =
+        load_time_standards(&standards_path, &config.gender_tabs, &config.stroke_names, &men_only).unwrap();
+    let results = parse_meet_file(&meet_path, &config, &mut Vec::new()).unwrap();
+
+    // Not a qualifying swim by exact age, but a unique qualifier by matched age
+    assert!(count_qualifiers(&results, &standards, 0.0).is_empty());
+    let group = ("Men".to_string(), "12".to_string());
+    let unique = count_unique_qualifiers(&results, &standards, 0.0);
+    assert_eq!(names(unique.get(&group)), vec!["Ann Able"]);
+
+    let mismatches = age_mismatches(&results, &standards, 0.0);
+    assert_eq!(mismatches.len(), 1);
+    assert_eq!(mismatches[0].name, "Ann Able");
+    assert_eq!(mismatches[0].age, "14");
+    assert_eq!(mismatches[0].age_group, "12");
+    assert_eq!(mismatches[0].standard, 31.0);
+}
+
+#[test]
+fn status_changes_between_standards_files() {
+    let dir = FixtureDir::new("status");
+    let previous_path = dir.0.join("timestandards_2024.xlsx");
+    let standards_path = dir.0.join("timestandards.xlsx");
+    let meet_path = dir.0.join("CAN-MBSK_Test_SCY_Men_11-12.xlsx");
+
+    // The free cut gets faster and the back cut slower
+    write_standards(&previous_path).unwrap();
+    write_standards_with(&standards_path, 30.0, "1:10.00").unwrap();
+    write_meet(&meet_path, &[
+        ("50 Free", vec![("Able, Ann", "30.50"), ("Baker, Bo", "29.50")]),
+        ("100 Back", vec![("Able, Ann", "1:08.00"), ("Baker, Bo", "1:05.00")]),
+    ]).unwrap();
+
+    let config = Config::default();
+    let men_only = ["Men".to_string()];
+    let (previous, _) =
+        load_time_standards(&previous_path, &config.gender_tabs, &config.stroke_names, &men_only).unwrap();
+    let (standards, _) =
+        load_time_standards(&standards_path, &config.gender_tabs, &config.stroke_names, &men_only).unwrap();
+    let results = parse_meet_file(&meet_path, &config, &mut Vec::new()).unwrap();
+
+    // Bo qualifies under both in both events, so only Ann changes
+    let changes = status_changes(&results, &previous, &standards, 0.0);
+    let summary: Vec<_> = changes.iter()
+        .map(|c| (c.name.as_str(), c.event.as_str(), c.newly_qualified))
+        .collect();
+    assert_eq!(summary, vec![("Ann Able", "100Bk", true), ("Ann Able", "50Fr", false)]);
+    assert_eq!(changes[1].previous_standard, Some(31.0));
+    assert_eq!(changes[1].standard, Some(30.0));
+}
+
+#[test]
+fn blank_names_are_not_counted_as_athletes() {
+    let dir = FixtureDir::new("blank-names");
+    let standards_path = dir.0.join("timestandards.xlsx");
+    let meet_path = dir.0.join("CAN-MBSK_Test_SCY_Men_11-12.xlsx");
+
+    write_standards(&standards_path).unwrap();
+    write_meet(&meet_path, &[
+        ("50 Free", vec![("Able, Ann", "30.50"), ("   ", "30.00")]),
+    ]).unwrap();
+
+    let config = Config::default();
+    let men_only = ["Men".to_string()];
+    let (standards, _) =
+        load_time_standards(&standards_path, &config.gender_tabs, &config.stroke_names, &men_only).unwrap();
+    let mut results = parse_meet_file(&meet_path, &config, &mut Vec::new()).unwrap();
+    assert_eq!(results.len(), 2);
+
+    // Results built through the library aren't trimmed by the parser
+    let mut untrimmed = results[0].clone();
+    untrimmed.name = "   ".to_string();
+    untrimmed.time = 29.00;
+    results.push(untrimmed);
+
+    let group = ("Men".to_string(), "12".to_string());
+    let unique = count_unique_qualifiers(&results, &standards, 0.0);
+    assert_eq!(names(unique.get(&group)), vec!["Ann Able"]);
+    let total = count_total_athletes(&results, &standards);
+    assert_eq!(names(total.get(&group)), vec!["Ann Able"]);
+    let best: Vec<_> = best_times(&results).into_iter().map(|r| r.name).collect();
+    assert_eq!(best, vec!["Ann Able"]);
+}
+
+#[test]
+fn qualifying_split_counts_against_the_50_standard() {
+    let dir = FixtureDir::new("splits");
+    let standards_path = dir.0.join("timestandards.xlsx");
+    let meet_path = dir.0.join("CAN-MBSK_Test_SCY_Men_11-12.xlsx");
+
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("Mens").unwrap();
+    sheet.write_string(0, 0, "Event").unwrap();
+    sheet.write_string(0, 1, "12").unwrap();
+    sheet.write_string(1, 0, "50 Free").unwrap();
+    sheet.write_number(1, 1, 31.0).unwrap();
+    sheet.write_string(2, 0, "100 Free").unwrap();
+    sheet.write_string(2, 1, "1:08.00").unwrap();
+    workbook.save(&standards_path).unwrap();
+
+    // Name in E, time in J and the 50 split in K
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("100 Free").unwrap();
+    let rows = [
+        ("Name", "Time", "Split"),
+        ("Able, Ann", "1:10.00", "30.50"),
+        ("Baker, Bo", "1:09.00", "32.00"),
+    ];
+    for (row, (name, time, split)) in rows.iter().enumerate() {
+        sheet.write_string(row as u32, 4, *name).unwrap();
+        sheet.write_string(row as u32, 9, *time).unwrap();
+        sheet.write_string(row as u32, 10, *split).unwrap();
+    }
+    workbook.save(&meet_path).unwrap();
+
+    let mut config = Config::default();
+    let men_only = ["Men".to_string()];
+    let (standards, _) =
+        load_time_standards(&standards_path, &config.gender_tabs, &config.stroke_names, &men_only).unwrap();
+
+    // Off by default: neither full swim makes the 100 cut
+    let results = parse_meet_file(&meet_path, &config, &mut Vec::new()).unwrap();
+    assert!(results.iter().all(|r| r.split_time.is_none()));
+    assert!(count_qualifiers(&results, &standards, 0.0).is_empty());
+
+    config.split_col = Some(10);
+    let results = parse_meet_file(&meet_path, &config, &mut Vec::new()).unwrap();
+    let counts = count_qualifiers(&results, &standards, 0.0);
+    let key = |event: &str| ("Men".to_string(), "12".to_string(), event.to_string());
+    assert_eq!(counts.get(&key("50Fr")), Some(&1));
+    assert_eq!(counts.get(&key("100Fr")), None);
+}
+
+#[test]
+fn swimmer_qualifying_only_in_prelims_is_counted() {
+    let dir = FixtureDir::new("prelims");
+    let standards_path = dir.0.join("timestandards.xlsx");
+    let meet_path = dir.0.join("CAN-MBSK_Test_SCY_Men_11-12.xlsx");
+    write_standards(&standards_path).unwrap();
+
+    // Name in E, final time in J and prelim time in K; the 12 cut is 31.00
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("50 Free").unwrap();
+    let rows = [
+        ("Name", "Time", "Prelim"),
+        ("Able, Ann", "31.50", "30.50"), // Under the cut in prelims only
+        ("Baker, Bo", "", "30.00"),      // Swam prelims only
+        ("Carter, Cy", "30.80", "31.20"), // Under the cut in the final only
+    ];
+    for (row, (name, time, prelim)) in rows.iter().enumerate() {
+        sheet.write_string(row as u32, 4, *name).unwrap();
+        sheet.write_string(row as u32, 9, *time).unwrap();
+        sheet.write_string(row as u32, 10, *prelim).unwrap();
+    }
+    workbook.save(&meet_path).unwrap();
+
+    let mut config = Config::default();
+    let men_only = ["Men".to_string()];
+    let (standards, _) =
+        load_time_standards(&standards_path, &config.gender_tabs, &config.stroke_names, &men_only).unwrap();
+    let key = ("Men".to_string(), "12".to_string(), "50Fr".to_string());
+
+    // Without a prelim column only the final is read
+    let results = parse_meet_file(&meet_path, &config, &mut Vec::new()).unwrap();
+    assert!(results.iter().all(|r| r.round.is_none()));
+    assert_eq!(count_qualifiers(&results, &standards, 0.0).get(&key), Some(&1));
+
+    config.prelim_col = Some(10);
+    let results = parse_meet_file(&meet_path, &config, &mut Vec::new()).unwrap();
+    let rounds: Vec<(&str, f64, Option<Round>)> = results.iter()
+        .map(|r| (r.name.as_str(), r.time, r.round))
+        .collect();
+    assert_eq!(rounds, [
+        ("Ann Able", 30.50, Some(Round::Prelim)),
+        ("Bo Baker", 30.00, Some(Round::Prelim)),
+        ("Cy Carter", 30.80, Some(Round::Final)),
+    ]);
+    assert_eq!(count_qualifiers(&results, &standards, 0.0).get(&key), Some(&3));
+
+    // Each policy still falls back to the prelim of a swimmer with no final
+    let qualifiers = |round_policy| {
+        let config = Config { round_policy, ..config.clone() };
+        let results = parse_meet_file(&meet_path, &config, &mut Vec::new()).unwrap();
+        count_qualifiers(&results, &standards, 0.0).get(&key).copied()
+    };
+    assert_eq!(qualifiers(RoundPolicy::Final), Some(2));
+    assert_eq!(qualifiers(RoundPolicy::Prelim), Some(2));
+}
+
+#[test]
+fn standards_event_names_show_raw_and_normalized() {
+    let dir = FixtureDir::new("list-events");
+    let standards_path = dir.0.join("timestandards.xlsx");
+
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("Mens").unwrap();
+    sheet.write_string(0, 0, "Event").unwrap();
+    sheet.write_string(0, 1, "12").unwrap();
+    for (row, name) in ["50 Free", "100 Backstroke", "50 Kick"].iter().enumerate() {
+        sheet.write_string(row as u32 + 1, 0, *name).unwrap();
+        sheet.write_number(row as u32 + 1, 1, 40.0).unwrap();
+    }
+    workbook.save(&standards_path).unwrap();
+
+    let config = Config::default();
+    let names = standards_event_names(&standards_path, &config.gender_tabs, config.standards_columns(),
+                                      &config.stroke_names).unwrap();
+    let listed: Vec<_> = names.iter()
+        .map(|n| (n.gender.as_str(), n.raw.as_str(), n.event.as_str(), n.known_stroke))
+        .collect();
+    assert_eq!(listed, vec![
+        ("Men", "50 Free", "50Fr", true),
+        ("Men", "100 Backstroke", "100Bk", true),
+        ("Men", "50 Kick", "50Kick", false),
+    ]);
+}
+
+#[test]
+fn mixed_format_standards_skip_only_the_unreadable_cell() {
+    let dir = FixtureDir::new("mixed-standards");
+    let standards_path = dir.0.join("timestandards.xlsx");
+
+    // Numbers, text and Excel times in one sheet, plus a typo ("1:O6.00")
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("Mens").unwrap();
+    for (col, label) in ["Event", "10&U", "11", "12"].iter().enumerate() {
+        sheet.write_string(0, col as u16, *label).unwrap();
+    }
+    let time_format = Format::new().set_num_format("mm:ss.00");
+    sheet.write_string(1, 0, "50 Free").unwrap();
+    sheet.write_number(1, 1, 35.0).unwrap();
+    sheet.write_string(1, 2, "33.00").unwrap();
+    let excel_time = ExcelDateTime::from_hms(0, 0, 31.0).unwrap();
+    sheet.write_datetime_with_format(1, 3, &excel_time, &time_format).unwrap();
+    sheet.write_string(2, 0, "100 Back").unwrap();
+    sheet.write_string(2, 1, "1:20.00").unwrap();
+    sheet.write_string(2, 2, "1:15.00").unwrap();
+    sheet.write_string(2, 3, "1:O6.00").unwrap();
+    workbook.save(&standards_path).unwrap();
+
+    let config = Config::default();
+    let men_only = ["Men".to_string()];
+    let (standards, _) =
+        load_time_standards(&standards_path, &config.gender_tabs, &config.stroke_names, &men_only).unwrap();
+
+    let cut = |event: &str, age: &str| {
+        standards["Men"][event].get(age).map(|cuts| (cuts[DEFAULT_CUT_LEVEL] * 100.0).round() / 100.0)
+    };
+    assert_eq!(cut("50Fr", "10"), Some(35.0));
+    assert_eq!(cut("50Fr", "11"), Some(33.0));
+    assert_eq!(cut("50Fr", "12"), Some(31.0));
+    assert_eq!(cut("100Bk", "11"), Some(75.0));
+    assert_eq!(cut("100Bk", "12"), None);
+}
+
+#[test]
+fn margin_is_positive_under_the_cut_and_negative_for_near_misses() {
+    let dir = FixtureDir::new("margin");
+    let standards_path = dir.0.join("timestandards.xlsx");
+    let meet_path = dir.0.join("CAN-MBSK_Test_SCY_Men_11-12.xlsx");
+
+    write_standards(&standards_path).unwrap();
+    write_meet(&meet_path, &[
+        ("50 Free", vec![("Able, Ann", "30.50"), ("Baker, Bo", "31.50")]),
+    ]).unwrap();
+
+    let config = Config::default();
+    let men_only = ["Men".to_string()];
+    let (standards, _) =
+        load_time_standards(&standards_path, &config.gender_tabs, &config.stroke_names, &men_only).unwrap();
+    let results = parse_meet_file(&meet_path, &config, &mut Vec::new()).unwrap();
+
+    // The 12 cut is 31.00
+    let qualifiers = qualifier_details(&results, &standards, 0.0);
+    assert_eq!(qualifiers.len(), 1);
+    assert_eq!(qualifiers[0].name, "Ann Able");
+    assert!((qualifiers[0].margin() - 0.5).abs() < 1e-9);
+
+    let near_misses = count_near_qualifiers(&results, &standards, 0.0, 5.0);
+    assert_eq!(near_misses.len(), 1);
+    assert_eq!(near_misses[0].name, "Bo Baker");
+    assert!((near_misses[0].margin() + 0.5).abs() < 1e-9);
+}
+
+#[test]
+fn tolerance_lets_a_swim_just_over_the_cut_qualify() {
+    let dir = FixtureDir::new("tolerance");
+    let standards_path = dir.0.join("timestandards.xlsx");
+    let meet_path = dir.0.join("CAN-MBSK_Test_SCY_Men_11-12.xlsx");
+
+    // A hundredth over the 31.00 cut for 12
+    write_standards(&standards_path).unwrap();
+    write_meet(&meet_path, &[("50 Free", vec![("Able, Ann", "31.01")])]).unwrap();
+
+    let config = Config::default();
+    let men_only = ["Men".to_string()];
+    let (standards, _) =
+        load_time_standards(&standards_path, &config.gender_tabs, &config.stroke_names, &men_only).unwrap();
+    let results = parse_meet_file(&meet_path, &config, &mut Vec::new()).unwrap();
+    let key = ("Men".to_string(), "12".to_string(), "50Fr".to_string());
+
+    assert_eq!(count_qualifiers(&results, &standards, 0.0).get(&key), None);
+    assert_eq!(count_qualifiers(&results, &standards, 0.01).get(&key), Some(&1));
+    let unique = count_unique_qualifiers(&results, &standards, 0.01);
+    assert_eq!(names(unique.get(&("Men".to_string(), "12".to_string()))), vec!["Ann Able"]);
+}
+
+#[test]
+fn tolerance_reports_the_published_cut() {
+    let dir = FixtureDir::new("tolerance-cut");
+    let standards_path = dir.0.join("timestandards.xlsx");
+    let meet_path = dir.0.join("CAN-MBSK_Test_SCY_Men_11-12.xlsx");
+
+    write_standards(&standards_path).unwrap();
+    write_meet(&meet_path, &[("50 Free", vec![("Able, Ann", "31.01"), ("Baker, Bo", "31.50")])]).unwrap();
+
+    let config = Config::default();
+    let men_only = ["Men".to_string()];
+    let (standards, _) =
+        load_time_standards(&standards_path, &config.gender_tabs, &config.stroke_names, &men_only).unwrap();
+    let results = parse_meet_file(&meet_path, &config, &mut Vec::new()).unwrap();
+
+    // Ann makes the 31.00 cut on the tolerance, but the Standard column and
+    // her Margin still go by 31.00
+    let qualifiers = qualifier_details(&results, &standards, 0.01);
+    assert_eq!(qualifiers.len(), 1);
+    assert_eq!(qualifiers[0].name, "Ann Able");
+    assert_eq!(qualifiers[0].standard, 31.0);
+    assert!((qualifiers[0].margin() + 0.01).abs() < 1e-9);
+
+    // Bo's near miss window is measured from the published cut too
+    let near_misses = count_near_qualifiers(&results, &standards, 0.01, 5.0);
+    assert_eq!(near_misses.len(), 1);
+    assert_eq!(near_misses[0].name, "Bo Baker");
+    assert_eq!(near_misses[0].standard, 31.0);
+    assert_eq!(standards["Men"]["50Fr"]["12"][DEFAULT_CUT_LEVEL], 31.0);
+}
+
+#[test]
+fn standards_columns_can_be_moved() {
+    let dir = FixtureDir::new("standards-columns");
+    let standards_path = dir.0.join("timestandards.xlsx");
+
+    // A category column before the events, ages from column C
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("Mens").unwrap();
+    for (col, label) in ["Category", "Event", "11", "12"].iter().enumerate() {
+        sheet.write_string(0, col as u16, *label).unwrap();
+    }
+    sheet.write_string(1, 0, "Sprint").unwrap();
+    sheet.write_string(1, 1, "50 Free").unwrap();
+    sheet.write_number(1, 2, 33.0).unwrap();
+    sheet.write_number(1, 3, 31.0).unwrap();
+    workbook.save(&standards_path).unwrap();
+
+    let config = Config { standards_event_col: Some(1), ..Config::default() };
+    let columns = config.standards_columns();
+    assert_eq!(columns, StandardsColumns { event: 1, first_age: 2 });
+
+    let men_only = ["Men".to_string()];
+    let (standards, event_orders) = load_time_standards_with(&standards_path, &config.gender_tabs, columns,
+                                                             &config.stroke_names, &men_only).unwrap();
+    assert_eq!(event_orders["Men"], vec!["50Fr"]);
+    assert_eq!(standards["Men"]["50Fr"]["11"][DEFAULT_CUT_LEVEL], 33.0);
+    assert_eq!(standards["Men"]["50Fr"]["12"][DEFAULT_CUT_LEVEL], 31.0);
+
+    // Age groups can't start at or before the event column
+    let backwards = StandardsColumns { event: 1, first_age: 1 };
+    let err = load_time_standards_with(&standards_path, &config.gender_tabs, backwards,
+                                       &config.stroke_names, &men_only).unwrap_err();
+    match err {
+        AnalysisError::InvalidStandards { problems, .. } => assert!(problems[0].contains("event column (B)")),
+        other => panic!("expected InvalidStandards, got {}", other),
+    }
+}
+
+#[test]
+fn custom_event_normalizer_reads_club_sheet_names() {
+    let dir = FixtureDir::new("event-normalizer");
+    let standards_path = dir.0.join("timestandards.xlsx");
+    let meet_path = dir.0.join("CAN-MBSK_Test_SCY_Men_11-12.xlsx");
+
+    write_standards(&standards_path).unwrap();
+    write_meet(&meet_path, &[
+        ("Sprint Free", vec![("Able, Ann", "30.50")]),
+        ("Warmup", vec![("Able, Ann", "5:00.00")]),
+        ("100 Back", vec![("Baker, Bo", "1:05.00")]),
+    ]).unwrap();
+
+    // The club's name for the 50 free; anything else goes to the built-in rules
+    // except the warmup sheet, which is skipped
+    let config = Config {
+        event_normalizer: Some(EventNormalizer::new(|name| match name {
+            "Sprint Free" => Some("50Fr".to_string()),
+            "Warmup" => None,
+            _ => normalize_any_event(name).map(|(event, _)| event),
+        })),
+        ..Config::default()
+    };
+    let men_only = ["Men".to_string()];
+    let (standards, _) =
+        load_time_standards(&standards_path, &config.gender_tabs, &config.stroke_names, &men_only).unwrap();
+
+    let results = parse_meet_file(&meet_path, &config, &mut Vec::new()).unwrap();
+    let events: Vec<&str> = results.iter().map(|r| r.event()).collect();
+    assert_eq!(events, ["50Fr", "100Bk"]);
+
+    let counts = count_qualifiers(&results, &standards, 0.0);
+    assert_eq!(counts.get(&("Men".to_string(), "12".to_string(), "50Fr".to_string())), Some(&1));
+}
+
+#[test]
+fn athletes_are_tallied_by_distinct_qualifying_events() {
+    let dir = FixtureDir::new("multi-event");
+    let standards_path = dir.0.join("timestandards.xlsx");
+    let meet_path = dir.0.join("CAN-MBSK_Test_SCY_Men_11-12.xlsx");
+
+    write_standards(&standards_path).unwrap();
+    write_meet(&meet_path, &[
+        ("50 Free", vec![
+            ("Able, Ann", "30.50"),
+            ("Able, Ann", "30.90"), // a second swim in the same event counts once
+            ("Baker, Bo", "30.00"),
+            ("Carter, Cy", "30.80"),
+        ]),
+        ("100 Back", vec![
+            ("Able, Ann", "1:05.00"),
+            ("Carter, Cy", "1:05.50"),
+            ("Baker, Bo", "1:10.00"),
+        ]),
+    ]).unwrap();
+
+    let config = Config::default();
+    let men_only = ["Men".to_string()];
+    let (standards, _) =
+        load_time_standards(&standards_path, &config.gender_tabs, &config.stroke_names, &men_only).unwrap();
+    let results = parse_meet_file(&meet_path, &config, &mut Vec::new()).unwrap();
+
+    let counts = count_qualifying_events(&results, &standards, 0.0);
+    let key = |name: &str| (name.to_string(), "Men".to_string(), "12".to_string());
+    assert_eq!(counts.get(&key("Ann Able")), Some(&2));
+    assert_eq!(counts.get(&key("Bo Baker")), Some(&1));
+    assert_eq!(counts.get(&key("Cy Carter")), Some(&2));
+
+    // Only athletes with two or more events, most first then by name
+    let rows = multi_event_qualifiers(&counts);
+    let listed: Vec<(&str, usize)> = rows.iter().map(|r| (r.name.as_str(), r.events)).collect();
+    assert_eq!(listed, [("Ann Able", 2), ("Cy Carter", 2)]);
+}
+
+#[test]
+fn no_result_sentinels_are_blank_in_standards_and_meets() {
+    let dir = FixtureDir::new("sentinels");
+    let standards_path = dir.0.join("timestandards.xlsx");
+    let meet_path = dir.0.join("CAN-MBSK_Test_SCY_Men_11-12.xlsx");
+
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("Mens").unwrap();
+    for (col, label) in ["Event", "10&U", "11", "12"].iter().enumerate() {
+        sheet.write_string(0, col as u16, *label).unwrap();
+    }
+    sheet.write_string(1, 0, "50 Free").unwrap();
+    sheet.write_string(1, 1, "---").unwrap();
+    sheet.write_string(1, 2, "N/A").unwrap();
+    sheet.write_number(1, 3, 31.0).unwrap();
+    sheet.write_string(2, 0, "100 Back").unwrap();
+    sheet.write_string(2, 1, "0.00").unwrap();
+    sheet.write_string(2, 2, "NaN").unwrap();
+    sheet.write_string(2, 3, "1:06.00").unwrap();
+    workbook.save(&standards_path).unwrap();
+
+    write_meet(&meet_path, &[
+        ("50 Free", vec![
+            ("Able, Ann", "30.50"),
+            ("Baker, Bo", "---"),
+            ("Carter, Cy", "0.00"),
+        ]),
+        ("100 Back", vec![
+            ("Able, Ann", "N/A"),
+            ("Baker, Bo", "NaN"),
+        ]),
+    ]).unwrap();
+
+    let config = Config::default();
+    let men_only = ["Men".to_string()];
+    let (standards, _) =
+        load_time_standards(&standards_path, &config.gender_tabs, &config.stroke_names, &men_only).unwrap();
+    // Missing cuts, not zero-second ones nobody could make
+    let ages = |event: &str| {
+        let mut ages: Vec<&str> = standards["Men"][event].keys().map(String::as_str).collect();
+        ages.sort();
+        ages
+    };
+    assert_eq!(ages("50Fr"), ["12"]);
+    assert_eq!(ages("100Bk"), ["12"]);
+
+    // Skipped like blank cells: no results and no unreadable time warnings
+    let mut warnings = Vec::new();
+    let results = parse_meet_file(&meet_path, &config, &mut warnings).unwrap();
+    let swims: Vec<(&str, f64)> = results.iter().map(|r| (r.name.as_str(), r.time)).collect();
+    assert_eq!(swims, [("Ann Able", 30.5)]);
+    assert!(warnings.iter().all(|w| w.category != WarningCategory::UnparseableTime), "{:?}", warnings);
+}
+
+#[test]
+fn events_without_a_standard_are_listed_after_the_standards_events() {
+    let dir = FixtureDir::new("meet-events");
+    let standards_path = dir.0.join("timestandards.xlsx");
+    let meet_path = dir.0.join("CAN-MBSK_Test_SCY_Men_11-12.xlsx");
+
+    write_standards(&standards_path).unwrap();
+    write_meet(&meet_path, &[
+        ("100 Fly", vec![("Able, Ann", "1:10.00")]),
+        ("50 Free", vec![("Able, Ann", "30.50")]),
+        ("25 Free", vec![("Baker, Bo", "15.00")]),
+    ]).unwrap();
+
+    let config = Config::default();
+    let men_only = ["Men".to_string()];
+    let (standards, event_orders) =
+        load_time_standards(&standards_path, &config.gender_tabs, &config.stroke_names, &men_only).unwrap();
+    let results = parse_meet_file(&meet_path, &config, &mut Vec::new()).unwrap();
+
+    let unmatched = unmatched_results(&results, &standards);
+    let event_orders = with_meet_events(&event_orders, &standards, &unmatched);
+    assert_eq!(event_orders["Men"], ["50Fr", "100Bk", "25Fr", "100Bu"]);
+
+    let swims = count_qualifiers(&results, &standards, 0.0);
+    let report = build_report(&standards, &event_orders, &swims, &HashMap::new(), &HashMap::new(),
+                              &HashMap::new(), &HashMap::new(), &HashMap::new(), &HashMap::new());
+    let rows: Vec<(&str, bool)> = report.genders[0].events.iter()
+        .map(|row| (row.event.as_str(), row.no_standard))
+        .collect();
+    assert_eq!(rows, [("50Fr", false), ("100Bk", false), ("25Fr", true), ("100Bu", true)]);
+}
+
+#[test]
+fn relay_named_by_total_distance_matches_its_legs_standard() {
+    let dir = FixtureDir::new("relay-standards");
+    let standards_path = dir.0.join("timestandards.xlsx");
+    let meet_path = dir.0.join("CAN-MBSK_Test_SCY_Men_11-12.xlsx");
+
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("Mens").unwrap();
+    for (col, label) in ["Event", "11", "12"].iter().enumerate() {
+        sheet.write_string(0, col as u16, *label).unwrap();
+    }
+    sheet.write_string(1, 0, "50 Free").unwrap();
+    sheet.write_number(1, 1, 33.0).unwrap();
+    sheet.write_number(1, 2, 31.0).unwrap();
+    sheet.write_string(2, 0, "4x50 Free Relay").unwrap();
+    sheet.write_string(2, 1, "2:10.00").unwrap();
+    sheet.write_string(2, 2, "2:05.00").unwrap();
+    workbook.save(&standards_path).unwrap();
+
+    write_meet(&meet_path, &[
+        ("200 Free Relay", vec![("Team A", "2:01.00"), ("Team B", "2:08.00")]),
+    ]).unwrap();
+
+    let config = Config::default();
+    let men_only = ["Men".to_string()];
+    let (standards, event_orders) =
+        load_time_standards(&standards_path, &config.gender_tabs, &config.stroke_names, &men_only).unwrap();
+    assert_eq!(event_orders["Men"], vec!["50Fr", "4x50FrR"]);
+
+    let results = parse_meet_file(&meet_path, &config, &mut Vec::new()).unwrap();
+    assert!(results.iter().all(|r| r.event() == "4x50FrR" && r.is_relay));
+
+    let counts = count_qualifiers(&results, &standards, 0.0);
+    assert_eq!(counts.get(&("Men".to_string(), "12".to_string(), "4x50FrR".to_string())), Some(&1));
+}
+
+#[test]
+fn age_group_columns_keep_the_standards_label() {
+    let dir = FixtureDir::new("age-labels");
+    let standards_path = dir.0.join("timestandards.xlsx");
+    let meet_path = dir.0.join("CAN-MBSK_Test_SCY_Men_9.xlsx");
+    write_standards(&standards_path).unwrap();
+    write_meet(&meet_path, &[("50 Free", vec![("Able, Ann", "34.00")])]).unwrap();
+
+    let config = Config::default();
+    let men_only = ["Men".to_string()];
+    let (standards, event_orders, labels) = load_time_standards_labeled(
+        &standards_path, &config.gender_tabs, StandardsColumns::default(), &config.stroke_names, &men_only,
+    ).unwrap();
+    assert_eq!(labels, HashMap::from([(("Men".to_string(), "10".to_string()), "10&U".to_string())]));
+
+    // A 9-year-old is matched on the bare age to the "10&U" group, so they're
+    // a unique qualifier there (the swim counts go by exact age)
+    let results = parse_meet_file(&meet_path, &config, &mut Vec::new()).unwrap();
+    let unique = count_unique_qualifiers(&results, &standards, 0.0);
+    assert!(unique[&("Men".to_string(), "10".to_string())].contains("Ann Able"));
+
+    let report = build_report(&standards, &event_orders, &HashMap::new(), &HashMap::new(), &HashMap::new(),
+                              &HashMap::new(), &unique, &HashMap::new(), &labels);
+    assert_eq!(report.genders[0].age_groups, ["10", "11", "12"]);
+    assert_eq!(report.genders[0].age_labels, ["10&U", "11", "12"]);
+}
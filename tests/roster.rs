@@ -0,0 +1,55 @@
+mod common;
+
+use std::collections::{HashMap, HashSet};
+
+use standards::{athlete_roster, write_roster_csv, RosterEntry};
+
+use common::FixtureDir;
+
+fn key(name: &str, sex: &str, age_group: &str) -> (String, String, String) {
+    (name.to_string(), sex.to_string(), age_group.to_string())
+}
+
+fn events(events: &[&str]) -> HashSet<String> {
+    events.iter().map(|event| event.to_string()).collect()
+}
+
+#[test]
+fn roster_lists_athletes_with_events_in_standards_order() {
+    let qualifying = HashMap::from([
+        (key("Cy Carter", "Men", "12"), events(&["50Fr"])),
+        (key("Ann Able", "Men", "12"), events(&["200Me", "50Fr", "100Bk"])),
+        (key("Bo Baker", "Men", "10"), events(&["100Bk", "25Fr"])),
+    ]);
+    let event_orders = HashMap::from([("Men".to_string(), vec!["50Fr".to_string(), "100Bk".to_string(),
+                                                               "200Me".to_string()])]);
+
+    let roster = athlete_roster(qualifying, &event_orders);
+    let rows: Vec<(&str, &str, Vec<&str>)> = roster.iter()
+        .map(|entry| {
+            let events = entry.events.iter().map(String::as_str).collect();
+            (entry.name.as_str(), entry.age_group.as_str(), events)
+        })
+        .collect();
+    assert_eq!(rows, [
+        ("Bo Baker", "10", vec!["100Bk", "25Fr"]), // Events missing from the standards go last
+        ("Ann Able", "12", vec!["50Fr", "100Bk", "200Me"]),
+        ("Cy Carter", "12", vec!["50Fr"]),
+    ]);
+}
+
+#[test]
+fn roster_csv_quotes_names_with_commas() {
+    let dir = FixtureDir::new("roster");
+    let path = dir.0.join("roster.csv");
+    let roster = [RosterEntry {
+        name: "Smith, John".to_string(),
+        sex: "Men".to_string(),
+        age_group: "12".to_string(),
+        events: vec!["50Fr".to_string(), "100Bk".to_string()],
+    }];
+    write_roster_csv(&path, &roster).unwrap();
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(contents, "Name,Sex,Age Group,Events\n\"Smith, John\",Men,12,50Fr;100Bk\n");
+}
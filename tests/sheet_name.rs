@@ -0,0 +1,42 @@
+use standards::{gender_label, sanitize_sheet_name};
+
+fn names(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn valid_names_are_kept() {
+    assert_eq!(sanitize_sheet_name("Mens SCY AA", &[]), "Mens SCY AA");
+}
+
+#[test]
+fn long_names_are_cut_to_31_characters() {
+    let name = sanitize_sheet_name("Womens SCY Provincial Championship AAA", &[]);
+    assert_eq!(name, "Womens SCY Provincial Champions");
+    assert_eq!(name.chars().count(), 31);
+}
+
+#[test]
+fn illegal_characters_are_dropped() {
+    assert_eq!(sanitize_sheet_name("Meet: Winter [Day 1/2]", &[]), "Meet Winter Day 12");
+    assert_eq!(sanitize_sheet_name("'Quoted'", &[]), "Quoted");
+    assert_eq!(sanitize_sheet_name("*?", &[]), "Sheet");
+}
+
+#[test]
+fn clashes_get_a_numeric_suffix() {
+    let existing = names(&["Mens", "mens (2)"]);
+    assert_eq!(sanitize_sheet_name("MENS", &existing), "MENS (3)");
+
+    // The suffix still fits within 31 characters
+    let long = "Womens SCY Provincial Championship";
+    let existing = names(&["Womens SCY Provincial Champions"]);
+    assert_eq!(sanitize_sheet_name(long, &existing), "Womens SCY Provincial Champ (2)");
+}
+
+#[test]
+fn gender_labels_match_the_standards_tabs() {
+    assert_eq!(gender_label("Men"), "Mens");
+    assert_eq!(gender_label("Women"), "Womens");
+    assert_eq!(gender_label("Mixed"), "Mixed");
+}
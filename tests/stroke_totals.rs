@@ -0,0 +1,42 @@
+mod common;
+
+use standards::{stroke_totals, EventCounts};
+
+use common::event;
+
+const AGES: [&str; 2] = ["10", "11"];
+
+fn swims(row: &EventCounts) -> Vec<usize> {
+    row.counts.iter().map(|c| c.qualifying_swims).collect()
+}
+
+#[test]
+fn distances_are_summed_per_stroke() {
+    let events = [
+        event("100Bk", &AGES, &[(1, 1), (0, 0)]),
+        event("50Fr", &AGES, &[(2, 2), (1, 1)]),
+        event("100Fr", &AGES, &[(3, 2), (4, 3)]),
+        event("50Bk", &AGES, &[(0, 0), (2, 1)]),
+    ];
+    let totals = stroke_totals(&events);
+
+    let labels: Vec<_> = totals.iter().map(|row| row.event.as_str()).collect();
+    assert_eq!(labels, vec!["Fr", "Bk"]);
+    assert_eq!(swims(&totals[0]), vec![5, 5]);
+    assert_eq!(swims(&totals[1]), vec![1, 2]);
+    assert_eq!(totals[0].counts[1].qualified_athletes, 4);
+}
+
+#[test]
+fn relays_follow_the_individual_strokes() {
+    let events = [
+        event("4x50FrR", &AGES, &[(1, 1), (1, 1)]),
+        event("200Me", &AGES, &[(1, 1), (0, 0)]),
+        event("50Bu", &AGES, &[(0, 0), (1, 1)]),
+        event("Mystery", &AGES, &[(9, 9), (9, 9)]),
+    ];
+    let totals = stroke_totals(&events);
+
+    let labels: Vec<_> = totals.iter().map(|row| row.event.as_str()).collect();
+    assert_eq!(labels, vec!["Bu", "Me", "Fr Relay"]);
+}
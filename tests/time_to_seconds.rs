@@ -0,0 +1,161 @@
+use calamine::{Data, ExcelDateTime, ExcelDateTimeType};
+use standards::{is_no_result, time_to_seconds, TimeCell};
+
+const EPSILON: f64 = 1e-9;
+
+fn assert_seconds(value: Data, expected: f64) {
+    match time_to_seconds(&value) {
+        TimeCell::Time(t) => assert!(
+            (t - expected).abs() < EPSILON,
+            "{:?}: expected {}, got {}", value, expected, t
+        ),
+        other => panic!("{:?}: expected {}, got {:?}", value, expected, other),
+    }
+}
+
+#[test]
+fn float_is_seconds() {
+    assert_seconds(Data::Float(28.45), 28.45);
+}
+
+#[test]
+fn int_is_seconds() {
+    assert_seconds(Data::Int(65), 65.0);
+}
+
+#[test]
+fn datetime_is_fraction_of_day() {
+    // 1:05.50 = 65.5 / 86400 days
+    let swim = ExcelDateTime::new(65.5 / 86400.0, ExcelDateTimeType::DateTime, false);
+    assert_seconds(Data::DateTime(swim), 65.5);
+}
+
+#[test]
+fn small_datetime_is_a_duration() {
+    // 0.001 days = 86.4 seconds
+    let swim = ExcelDateTime::new(0.001, ExcelDateTimeType::DateTime, false);
+    assert_seconds(Data::DateTime(swim), 86.4);
+}
+
+#[test]
+fn time_of_day_keeps_minutes_and_seconds() {
+    // 12:01:32 PM is a 1:32 swim exported as a clock time
+    let clock = ExcelDateTime::new((12.0 * 3600.0 + 92.0) / 86400.0, ExcelDateTimeType::DateTime, false);
+    match time_to_seconds(&Data::DateTime(clock)) {
+        TimeCell::ClockTime(t) => assert!((t - 92.0).abs() < 1e-6, "got {}", t),
+        other => panic!("expected a clock time, got {:?}", other),
+    }
+}
+
+#[test]
+fn datetime_over_an_hour_is_a_duration() {
+    // A 1:02:03.5 swim in an h:mm:ss cell keeps its hour
+    let swim = ExcelDateTime::new(3723.5 / 86400.0, ExcelDateTimeType::DateTime, false);
+    assert_seconds(Data::DateTime(swim), 3723.5);
+}
+
+#[test]
+fn whole_days_are_dates() {
+    let date = ExcelDateTime::new(45000.5, ExcelDateTimeType::DateTime, false);
+    assert_eq!(time_to_seconds(&Data::DateTime(date)), TimeCell::Unparseable);
+}
+
+#[test]
+fn string_minutes_seconds() {
+    assert_seconds(Data::String("1:05.50".to_string()), 65.5);
+    assert_seconds(Data::String("10:00.00".to_string()), 600.0);
+}
+
+#[test]
+fn seconds_past_sixty_are_unparseable() {
+    assert_eq!(time_to_seconds(&Data::String("2:65.00".to_string())), TimeCell::Unparseable);
+    assert_eq!(time_to_seconds(&Data::String("1:60.00".to_string())), TimeCell::Unparseable);
+    assert_seconds(Data::String("2:59.99".to_string()), 179.99);
+}
+
+#[test]
+fn string_hours_minutes_seconds() {
+    assert_seconds(Data::String("1:02:03.5".to_string()), 3723.5);
+    assert_seconds(Data::String("1:05:30.5".to_string()), 3930.5);
+    assert_seconds(Data::String("18:42.11".to_string()), 1122.11);
+    assert_eq!(time_to_seconds(&Data::String("1::30".to_string())), TimeCell::Unparseable);
+}
+
+#[test]
+fn minutes_past_an_hour_match_the_hour_field() {
+    // M:SS with 60 or more minutes reads the same as the H:MM:SS form
+    assert_seconds(Data::String("65:30.00".to_string()), 3930.0);
+    assert_seconds(Data::String("1:05:30.00".to_string()), 3930.0);
+    assert_seconds(Data::String("59:59.99".to_string()), 3599.99);
+    assert_seconds(Data::String("1:00:00.00".to_string()), 3600.0);
+}
+
+#[test]
+fn hour_form_rejects_minutes_or_seconds_past_59() {
+    // As the M:SS form rejects "2:65.00"
+    for time in ["1:75:99", "1:60:00", "1:05:60", "1:05:75.5"] {
+        assert_eq!(time_to_seconds(&Data::String(time.to_string())), TimeCell::Unparseable, "{}", time);
+    }
+    assert_seconds(Data::String("1:59:59.99".to_string()), 7199.99);
+    // A decimal comma goes through the same check
+    assert_eq!(time_to_seconds(&Data::String("1:05:61,5".to_string())), TimeCell::Unparseable);
+    assert_seconds(Data::String("1:05:30,5".to_string()), 3930.5);
+}
+
+#[test]
+fn string_plain_seconds() {
+    assert_seconds(Data::String("28.45".to_string()), 28.45);
+    assert_seconds(Data::String("  31.2 ".to_string()), 31.2);
+}
+
+#[test]
+fn string_decimal_comma() {
+    assert_seconds(Data::String("1:02,34".to_string()), 62.34);
+    assert_seconds(Data::String("28,45".to_string()), 28.45);
+    assert_seconds(Data::String("29,87".to_string()), 29.87);
+    // A decimal point is left as it is
+    assert_seconds(Data::String("1:02.34".to_string()), 62.34);
+}
+
+#[test]
+fn empty_and_nan_are_empty() {
+    assert_eq!(time_to_seconds(&Data::String(String::new())), TimeCell::Empty);
+    assert_eq!(time_to_seconds(&Data::String("   ".to_string())), TimeCell::Empty);
+    assert_eq!(time_to_seconds(&Data::String("NaN".to_string())), TimeCell::Empty);
+    assert_eq!(time_to_seconds(&Data::Empty), TimeCell::Empty);
+}
+
+#[test]
+fn no_result_sentinels_are_empty() {
+    for sentinel in ["---", "N/A", "n/a", "NaN", "-", "None"] {
+        assert_eq!(time_to_seconds(&Data::String(sentinel.to_string())), TimeCell::Empty, "{}", sentinel);
+        assert!(is_no_result(sentinel), "{}", sentinel);
+    }
+    assert!(!is_no_result("NT"));
+}
+
+#[test]
+fn zero_times_are_empty() {
+    assert_eq!(time_to_seconds(&Data::String("0.00".to_string())), TimeCell::Empty);
+    assert_eq!(time_to_seconds(&Data::String("0:00.00".to_string())), TimeCell::Empty);
+    assert_eq!(time_to_seconds(&Data::Float(0.0)), TimeCell::Empty);
+    assert_eq!(time_to_seconds(&Data::Int(0)), TimeCell::Empty);
+}
+
+#[test]
+fn status_codes() {
+    assert_eq!(time_to_seconds(&Data::String("DQ".to_string())), TimeCell::Status("DQ".to_string()));
+    assert_eq!(time_to_seconds(&Data::String("scr".to_string())), TimeCell::Status("SCR".to_string()));
+    assert_eq!(
+        time_to_seconds(&Data::String("DQ 7.2 false start".to_string())),
+        TimeCell::Status("DQ".to_string())
+    );
+}
+
+#[test]
+fn unsupported_values_are_unparseable() {
+    assert_eq!(time_to_seconds(&Data::Bool(true)), TimeCell::Unparseable);
+    assert_eq!(time_to_seconds(&Data::String("fast".to_string())), TimeCell::Unparseable);
+    assert_eq!(time_to_seconds(&Data::String("1:2:3:4".to_string())), TimeCell::Unparseable);
+    assert_eq!(time_to_seconds(&Data::Bool(false)).seconds(), None);
+}
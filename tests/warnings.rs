@@ -0,0 +1,34 @@
+use standards::{summarize_warnings, Warning, WarningCategory};
+
+#[test]
+fn display_leaves_out_unknown_location() {
+    let warning = Warning::new(WarningCategory::ShortRows, "2 rows end early")
+        .in_file("CAN-MBSK_Test_SCY_Men_12.xlsx")
+        .in_sheet("50 Free");
+    assert_eq!(warning.to_string(), "CAN-MBSK_Test_SCY_Men_12.xlsx sheet '50 Free': 2 rows end early");
+    
+    let warning = Warning::new(WarningCategory::UnmatchedEvent, "no standard for Men 25Fr");
+    assert_eq!(warning.to_string(), "no standard for Men 25Fr");
+}
+
+#[test]
+fn summary_counts_each_category() {
+    let warnings = [
+        Warning::new(WarningCategory::SkippedSheet, "not an event name"),
+        Warning::new(WarningCategory::UnparseableTime, "time 'fast' could not be read").at_row(3),
+        Warning::new(WarningCategory::UnparseableTime, "time 'x' could not be read").at_row(7),
+    ];
+    assert_eq!(summarize_warnings(&warnings), "2 unparseable_time, 1 skipped_sheet");
+    assert_eq!(summarize_warnings(&[]), "");
+}
+
+#[test]
+fn unreadable_sheets_have_their_own_category() {
+    let warnings = [
+        Warning::new(WarningCategory::SkippedSheet, "not an event name"),
+        Warning::new(WarningCategory::UnreadableSheet, "could not read sheet").in_sheet("50 Free"),
+        Warning::new(WarningCategory::UnreadableSheet, "could not read sheet").in_sheet("100 Back"),
+    ];
+    assert_eq!(WarningCategory::UnreadableSheet.name(), "unreadable_sheet");
+    assert_eq!(summarize_warnings(&warnings), "2 unreadable_sheet, 1 skipped_sheet");
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use rust_xlsxwriter::Workbook;
+use std::path::{Path, PathBuf};
+
+use standards::{parse_meet_file, AnalysisError, Config};
+
+use common::FixtureDir;
+
+// Legacy BIFF workbook written by tests/fixtures/make_xls.py
+fn xls_fixture() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/CAN-MBSK_Legacy_SCY_Men_11-12.xls")
+}
+
+// Rows of the fixture's "50 Free" sheet: name in column E, time in column J
+const ROWS: [(&str, &str); 3] = [("Name", "Time"), ("Able, Ann", "30.50"), ("Baker, Bo", "32.10")];
+
+// The same swims as the .xls fixture, as an .xlsx workbook at `path`
+fn write_xlsx(path: &Path) {
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("50 Free").unwrap();
+    for (row, (name, time)) in ROWS.iter().enumerate() {
+        sheet.write_string(row as u32, 4, *name).unwrap();
+        sheet.write_string(row as u32, 9, *time).unwrap();
+    }
+    workbook.save(path).unwrap();
+}
+
+fn swims(path: &Path) -> Vec<(String, f64)> {
+    parse_meet_file(path, &Config::default(), &mut Vec::new())
+        .unwrap()
+        .into_iter()
+        .map(|r| (r.name, r.time))
+        .collect()
+}
+
+#[test]
+fn legacy_xls_and_xlsx_read_the_same() {
+    let dir = FixtureDir::new("xlsx-format");
+    let xlsx_path = dir.0.join("CAN-MBSK_Modern_SCY_Men_11-12.xlsx");
+    write_xlsx(&xlsx_path);
+
+    let expected = vec![("Ann Able".to_string(), 30.50), ("Bo Baker".to_string(), 32.10)];
+    assert_eq!(swims(&xls_fixture()), expected);
+    assert_eq!(swims(&xlsx_path), expected);
+}
+
+#[test]
+fn extension_that_disagrees_with_the_contents_is_an_error() {
+    let dir = FixtureDir::new("renamed-format");
+
+    // An .xlsx export renamed to .xls
+    let renamed_xlsx = dir.0.join("CAN-MBSK_Renamed_SCY_Men_11-12.xls");
+    write_xlsx(&renamed_xlsx);
+    let err = parse_meet_file(&renamed_xlsx, &Config::default(), &mut Vec::new()).unwrap_err();
+    assert!(matches!(err, AnalysisError::WorkbookFormat { ref extension, ref actual, .. }
+                     if extension == "xls" && actual == "xlsx"), "{}", err);
+
+    // And a legacy file renamed to .xlsx
+    let renamed_xls = dir.0.join("CAN-MBSK_Renamed_SCY_Men_11-12.xlsx");
+    std::fs::copy(xls_fixture(), &renamed_xls).unwrap();
+    let err = parse_meet_file(&renamed_xls, &Config::default(), &mut Vec::new()).unwrap_err();
+    assert!(matches!(err, AnalysisError::WorkbookFormat { ref extension, ref actual, .. }
+                     if extension == "xlsx" && actual == "xls"), "{}", err);
+}
+
+#[test]
+fn damaged_workbook_is_reported_as_corrupt() {
+    let dir = FixtureDir::new("corrupt-format");
+
+    // Starts like an .xlsx (a zip archive) but is cut off
+    let truncated = dir.0.join("CAN-MBSK_Truncated_SCY_Men_11-12.xlsx");
+    std::fs::write(&truncated, b"PK\x03\x04\x14\x00\x00\x00").unwrap();
+    let err = parse_meet_file(&truncated, &Config::default(), &mut Vec::new()).unwrap_err();
+    assert!(matches!(err, AnalysisError::CorruptWorkbook { .. }), "{}", err);
+    assert_eq!(err.skip_reason(), "corrupt");
+
+    let protected = AnalysisError::ProtectedWorkbook { path: truncated.clone() };
+    assert_eq!(protected.skip_reason(), "protected");
+}